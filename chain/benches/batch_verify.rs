@@ -0,0 +1,59 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use eos_chain::{BatchVerifier, Checksum256, PublicKey, Signature};
+use keys::secret::SecretKey;
+
+const PRODUCER_KEYS: [&str; 3] = [
+    "5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3",
+    "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4",
+    "5KJVA9P4xsiRC3zPy1KPa3GA6ffvmyZSxhKPbE924YJphvSCG4F",
+];
+
+fn signed_triples(count: usize) -> Vec<(Checksum256, Signature, PublicKey)> {
+    let sks: Vec<_> = PRODUCER_KEYS.iter().map(|wif| SecretKey::from_wif(wif).unwrap()).collect();
+    (0..count).map(|i| {
+        let sk = &sks[i % sks.len()];
+        let digest = Checksum256::hash_from_slice(&(i as u64).to_le_bytes());
+        let signature = sk.sign_hash(digest.as_bytes()).unwrap().into();
+        let pk: PublicKey = keys::public::PublicKey::from(sk).into();
+        (digest, signature, pk)
+    }).collect()
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+    for count in [16usize, 256].iter() {
+        let triples = signed_triples(*count);
+
+        group.bench_with_input(BenchmarkId::new("per_call", count), &triples, |b, triples| {
+            b.iter(|| {
+                for (digest, signature, pk) in triples {
+                    pk.verify(digest.as_bytes(), signature).unwrap();
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("batch", count), &triples, |b, triples| {
+            b.iter(|| {
+                let mut verifier = BatchVerifier::new();
+                for (digest, signature, pk) in triples {
+                    verifier.push(*digest, signature, pk).unwrap();
+                }
+                verifier.verify().unwrap();
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("batch_parallel_4", count), &triples, |b, triples| {
+            b.iter(|| {
+                let mut verifier = BatchVerifier::new();
+                for (digest, signature, pk) in triples {
+                    verifier.push(*digest, signature, pk).unwrap();
+                }
+                verifier.verify_parallel(4).unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
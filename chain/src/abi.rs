@@ -0,0 +1,243 @@
+//! The binary form of a contract ABI, as packed into `setabi`
+//! <https://github.com/EOSIO/eos/blob/master/libraries/chain/include/eosio/chain/abi_def.hpp>
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{ActionName, NumBytes, Read, ReadError, SerializeData, TableName, Write, WriteError};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct TypeDef {
+    pub new_type_name: String,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct FieldDef {
+    pub name: String,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct StructDef {
+    pub name: String,
+    #[cfg_attr(feature = "std", serde(default))]
+    pub base: String,
+    pub fields: Vec<FieldDef>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ActionDef {
+    pub name: ActionName,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: String,
+    #[cfg_attr(feature = "std", serde(default))]
+    pub ricardian_contract: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct TableDef {
+    pub name: TableName,
+    pub index_type: String,
+    #[cfg_attr(feature = "std", serde(default))]
+    pub key_names: Vec<String>,
+    #[cfg_attr(feature = "std", serde(default))]
+    pub key_types: Vec<String>,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ClausePair {
+    pub id: String,
+    pub body: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ErrorMessage {
+    pub error_code: u64,
+    pub error_msg: String,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct AbiExtension {
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: u16,
+    pub data: Vec<u8>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct VariantDef {
+    pub name: String,
+    pub types: Vec<String>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ActionResultDef {
+    pub name: ActionName,
+    pub result_type: String,
+}
+
+/// A contract ABI, parses from the JSON produced by `eosio-cpp` and packs into
+/// the binary form `setabi` expects.
+///
+/// `variants` (abi/1.1) and `action_results` (abi/1.2) are binary extensions,
+/// they are always written and are read only when the bytes are present.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "std", serde(default))]
+pub struct AbiDef {
+    pub version: String,
+    pub types: Vec<TypeDef>,
+    pub structs: Vec<StructDef>,
+    pub actions: Vec<ActionDef>,
+    pub tables: Vec<TableDef>,
+    pub ricardian_clauses: Vec<ClausePair>,
+    pub error_messages: Vec<ErrorMessage>,
+    pub abi_extensions: Vec<AbiExtension>,
+    pub variants: Vec<VariantDef>,
+    pub action_results: Vec<ActionResultDef>,
+}
+
+impl AbiDef {
+    #[cfg(feature = "std")]
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        serde_json::from_str(json).map_err(|e| crate::Error::InvalidAbiJson(e.to_string()))
+    }
+
+    /// Unpack the ABI stored on chain, e.g. the `abi` of `get_raw_code_and_abi`.
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        AbiDef::read(bytes, &mut 0).map_err(crate::Error::BytesReadError)
+    }
+
+    /// Pack the ABI as the `abi` of `setabi`.
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        self.to_serialize_data()
+    }
+}
+
+impl NumBytes for AbiDef {
+    fn num_bytes(&self) -> usize {
+        self.version.num_bytes()
+            + self.types.num_bytes()
+            + self.structs.num_bytes()
+            + self.actions.num_bytes()
+            + self.tables.num_bytes()
+            + self.ricardian_clauses.num_bytes()
+            + self.error_messages.num_bytes()
+            + self.abi_extensions.num_bytes()
+            + self.variants.num_bytes()
+            + self.action_results.num_bytes()
+    }
+}
+
+impl Read for AbiDef {
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let mut abi = AbiDef {
+            version: String::read(bytes, pos)?,
+            types: Vec::<TypeDef>::read(bytes, pos)?,
+            structs: Vec::<StructDef>::read(bytes, pos)?,
+            actions: Vec::<ActionDef>::read(bytes, pos)?,
+            tables: Vec::<TableDef>::read(bytes, pos)?,
+            ricardian_clauses: Vec::<ClausePair>::read(bytes, pos)?,
+            error_messages: Vec::<ErrorMessage>::read(bytes, pos)?,
+            abi_extensions: Vec::<AbiExtension>::read(bytes, pos)?,
+            variants: Vec::new(),
+            action_results: Vec::new(),
+        };
+        if *pos < bytes.len() {
+            abi.variants = Vec::<VariantDef>::read(bytes, pos)?;
+        }
+        if *pos < bytes.len() {
+            abi.action_results = Vec::<ActionResultDef>::read(bytes, pos)?;
+        }
+
+        Ok(abi)
+    }
+}
+
+impl Write for AbiDef {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.version.write(bytes, pos)?;
+        self.types.write(bytes, pos)?;
+        self.structs.write(bytes, pos)?;
+        self.actions.write(bytes, pos)?;
+        self.tables.write(bytes, pos)?;
+        self.ricardian_clauses.write(bytes, pos)?;
+        self.error_messages.write(bytes, pos)?;
+        self.abi_extensions.write(bytes, pos)?;
+        self.variants.write(bytes, pos)?;
+        self.action_results.write(bytes, pos)
+    }
+}
+
+impl SerializeData for AbiDef {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    const HELLO_ABI: &str = r#"{
+        "version": "eosio::abi/1.1",
+        "types": [],
+        "structs": [{"name": "hi", "base": "", "fields": [{"name": "user", "type": "name"}]}],
+        "actions": [{"name": "hi", "type": "hi", "ricardian_contract": ""}],
+        "tables": [],
+        "ricardian_clauses": [],
+        "variants": []
+    }"#;
+
+    #[test]
+    fn abi_from_json_should_work() {
+        let abi = AbiDef::from_json(HELLO_ABI).unwrap();
+        assert_eq!(abi.version, "eosio::abi/1.1");
+        assert_eq!(abi.actions[0].name, ActionName::from_str("hi").unwrap());
+        assert_eq!(abi.structs[0].fields[0].type_, "name");
+        assert!(abi.error_messages.is_empty());
+
+        assert!(AbiDef::from_json("{\"version\": 1}").is_err());
+    }
+
+    #[test]
+    fn abi_serialize_should_work() {
+        let abi = AbiDef::from_json(HELLO_ABI).unwrap();
+        let data = abi.to_bytes().unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "0e656f73696f3a3a6162692f312e31000102686900010475736572046e616d6501000000000000806b02686900000000000000"
+        );
+        assert_eq!(AbiDef::from_bytes(&data).unwrap(), abi);
+    }
+
+    #[test]
+    fn abi_deserialize_without_extensions_should_work() {
+        // an abi/1.0 binary stops right after abi_extensions
+        let data = hex::decode("0e656f73696f3a3a6162692f312e3000000000000000").unwrap();
+        let abi = AbiDef::from_bytes(&data).unwrap();
+        assert_eq!(abi.version, "eosio::abi/1.0");
+        assert!(abi.variants.is_empty() && abi.action_results.is_empty());
+    }
+}
@@ -0,0 +1,214 @@
+//! Prove an action was executed in a block, e.g. to relay it to another chain.
+//!
+//! The action receipt is proven against the `action_mroot` of the block header and
+//! the block id against the blockroot merkle of a later block the verifier trusts,
+//! e.g. the one of a `LightClient` head.
+use alloc::vec::Vec;
+use crate::{
+    merkle, get_proof, verify_proof, Action, ActionReceipt, Checksum256, Digest, NumBytes, Read,
+    SerializeData, SignedBlockHeader, Write
+};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionProofError {
+    /// There is no receipt at this position of the block
+    ReceiptOutOfRange(usize),
+    /// The action is not the one the receipt digests
+    ActionDigestMismatch,
+    /// The receipt is not in the `action_mroot` of the block header
+    InvalidActionPath,
+    /// The block is not in the blockroot merkle
+    InvalidBlockrootPath,
+}
+
+impl From<ActionProofError> for crate::Error {
+    fn from(e: ActionProofError) -> crate::Error {
+        crate::Error::ActionProofError(e)
+    }
+}
+
+/// The `action_mroot` of a block, `receipts` are in execution order.
+pub fn action_mroot(receipts: &[ActionReceipt]) -> crate::Result<Checksum256> {
+    merkle(action_receipt_digests(receipts)?)
+}
+
+/// The merkle path from the digest of the receipt at `index` to the `action_mroot`.
+pub fn action_receipt_proof(receipts: &[ActionReceipt], index: usize) -> crate::Result<Vec<Checksum256>> {
+    if index >= receipts.len() {
+        return Err(ActionProofError::ReceiptOutOfRange(index).into());
+    }
+    get_proof(index, action_receipt_digests(receipts)?)
+}
+
+fn action_receipt_digests(receipts: &[ActionReceipt]) -> crate::Result<Vec<Checksum256>> {
+    receipts.iter().map(Digest::digest).collect()
+}
+
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ActionProof {
+    pub receipt: ActionReceipt,
+    pub action: Action,
+    /// Merkle path from the receipt digest to the `action_mroot` of `block_header`
+    pub action_path: Vec<Checksum256>,
+    pub block_header: SignedBlockHeader,
+    /// Merkle path from the block id to the blockroot merkle root of a later block,
+    /// the block is leaf `block_num - 1` as the merkle starts at block 1
+    pub blockroot_path: Vec<Checksum256>,
+}
+
+impl ActionProof {
+    /// Prove the action of the receipt at `index` of a block, `receipts` are all the
+    /// action receipts of the block in execution order.
+    pub fn new(
+        receipts: &[ActionReceipt],
+        index: usize,
+        action: Action,
+        block_header: SignedBlockHeader,
+        blockroot_path: Vec<Checksum256>,
+    ) -> crate::Result<Self> {
+        let action_path = action_receipt_proof(receipts, index)?;
+        Ok(ActionProof {
+            receipt: receipts[index].clone(),
+            action,
+            action_path,
+            block_header,
+            blockroot_path,
+        })
+    }
+
+    /// Check the action, its receipt and the block header are linked to `blockroot_root`,
+    /// the blockroot merkle root of a block whose header was validated, e.g. by a `LightClient`.
+    pub fn verify(&self, blockroot_root: Checksum256) -> crate::Result<()> {
+        if self.action.digest()? != self.receipt.act_digest {
+            return Err(ActionProofError::ActionDigestMismatch.into());
+        }
+        let header = &self.block_header.block_header;
+        if !verify_proof(&self.action_path, self.receipt.digest()?, header.action_mroot) {
+            return Err(ActionProofError::InvalidActionPath.into());
+        }
+        if !verify_proof(&self.blockroot_path, self.block_header.id()?, blockroot_root) {
+            return Err(ActionProofError::InvalidBlockrootPath.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::str::FromStr;
+    use crate::{AccountName, ActionName, IncrementalMerkle, PermissionLevel, utils::flat_map::FlatMap, UnsignedInt};
+
+    fn action(memo: u8) -> Action {
+        Action {
+            account: AccountName::from_str("eosio.token").unwrap(),
+            name: ActionName::from_str("transfer").unwrap(),
+            authorization: vec![PermissionLevel::from_str("alice", "active").unwrap()],
+            data: vec![memo; 8],
+        }
+    }
+
+    fn receipt(action: &Action, global_sequence: u64) -> ActionReceipt {
+        let receiver = AccountName::from_str("eosio.token").unwrap();
+        ActionReceipt {
+            receiver,
+            act_digest: action.digest().unwrap(),
+            global_sequence,
+            recv_sequence: global_sequence,
+            auth_sequence: FlatMap::new(AccountName::from_str("alice").unwrap(), global_sequence),
+            code_sequence: UnsignedInt::from(1u32),
+            abi_sequence: UnsignedInt::from(1u32),
+        }
+    }
+
+    fn block_ids(count: u8) -> Vec<Checksum256> {
+        (1..=count).map(|i| Checksum256::hash_from_slice(&[i])).collect()
+    }
+
+    fn proof(index: usize) -> (ActionProof, Checksum256) {
+        let actions: Vec<Action> = (0..5).map(action).collect();
+        let receipts: Vec<ActionReceipt> = actions.iter().zip(100..).map(|(a, seq)| receipt(a, seq)).collect();
+
+        let mut header = SignedBlockHeader::default();
+        header.block_header.action_mroot = action_mroot(&receipts).unwrap();
+        // the block is block 4, leaf 3 of the blockroot merkle of block 8
+        let mut ids = block_ids(7);
+        header.block_header.previous = ids[2];
+        header.block_header.previous.set_hash0((header.block_header.previous.hash0() & 0xffffffff00000000) | 0x03000000);
+        ids[3] = header.id().unwrap();
+
+        let mut blockroot_merkle = IncrementalMerkle::default();
+        for id in ids.iter() {
+            blockroot_merkle.append(*id).unwrap();
+        }
+        let blockroot_path = get_proof(3, ids).unwrap();
+
+        let proof = ActionProof::new(&receipts, index, actions[index].clone(), header, blockroot_path).unwrap();
+        (proof, blockroot_merkle.get_root())
+    }
+
+    #[test]
+    fn action_mroot_should_work() {
+        let receipts: Vec<ActionReceipt> = (0..3).map(|i| receipt(&action(i), 1)).collect();
+        let digests: Vec<Checksum256> = receipts.iter().map(|r| r.digest().unwrap()).collect();
+        assert_eq!(action_mroot(&receipts).unwrap(), merkle(digests.clone()).unwrap());
+        assert_eq!(action_mroot(&receipts[..1]).unwrap(), digests[0]);
+        assert_eq!(action_mroot(&[]).unwrap(), Checksum256::default());
+
+        let path = action_receipt_proof(&receipts, 2).unwrap();
+        assert!(verify_proof(&path, digests[2], action_mroot(&receipts).unwrap()));
+        assert!(!verify_proof(&path, digests[1], action_mroot(&receipts).unwrap()));
+        match action_receipt_proof(&receipts, 3) {
+            Err(crate::Error::ActionProofError(ActionProofError::ReceiptOutOfRange(3))) => {}
+            _ => panic!("the block has 3 receipts"),
+        }
+    }
+
+    #[test]
+    fn action_proof_should_verify() {
+        for index in 0..5 {
+            let (proof, blockroot_root) = proof(index);
+            assert_eq!(proof.block_header.block_num(), 4);
+            assert!(proof.verify(blockroot_root).is_ok());
+
+            let data = proof.to_serialize_data().unwrap();
+            assert_eq!(ActionProof::read(&data, &mut 0).unwrap(), proof);
+        }
+    }
+
+    #[test]
+    fn tampered_action_proof_should_not_verify() {
+        let (proof, blockroot_root) = proof(1);
+
+        let mut tampered = proof.clone();
+        tampered.action.data[0] = 42;
+        match tampered.verify(blockroot_root) {
+            Err(crate::Error::ActionProofError(ActionProofError::ActionDigestMismatch)) => {}
+            _ => panic!("the action was changed"),
+        }
+
+        let mut tampered = proof.clone();
+        tampered.receipt.global_sequence += 1;
+        match tampered.verify(blockroot_root) {
+            Err(crate::Error::ActionProofError(ActionProofError::InvalidActionPath)) => {}
+            _ => panic!("the receipt was changed"),
+        }
+
+        let mut tampered = proof.clone();
+        tampered.block_header.block_header.confirmed = 1;
+        match tampered.verify(blockroot_root) {
+            Err(crate::Error::ActionProofError(ActionProofError::InvalidBlockrootPath)) => {}
+            _ => panic!("the header was changed"),
+        }
+
+        match proof.verify(Checksum256::hash_from_slice(b"another root")) {
+            Err(crate::Error::ActionProofError(ActionProofError::InvalidBlockrootPath)) => {}
+            _ => panic!("the block is not in this merkle"),
+        }
+    }
+}
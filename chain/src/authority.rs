@@ -0,0 +1,247 @@
+//! <https://github.com/EOSIO/eos/blob/master/libraries/chain/include/eosio/chain/authority.hpp>
+use alloc::vec;
+use alloc::vec::Vec;
+use codec::{Encode, Decode};
+use crate::{NumBytes, PermissionLevel, PublicKey, Read, Write};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// A weighted threshold of keys, accounts and delays that satisfies a permission.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct Authority {
+    pub threshold: u32,
+    pub keys: Vec<KeyWeight>,
+    pub accounts: Vec<PermissionLevelWeight>,
+    pub waits: Vec<WaitWeight>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+#[repr(C)]
+pub struct KeyWeight {
+    pub key: PublicKey,
+    pub weight: u16,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct PermissionLevelWeight {
+    pub permission: PermissionLevel,
+    pub weight: u16,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct WaitWeight {
+    pub wait_sec: u32,
+    pub weight: u16,
+}
+
+/// Reasons nodeos rejects an authority in `updateauth` or `newaccount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityError {
+    ZeroThreshold,
+    /// More than 65536 keys, accounts and waits in total
+    TooManyEntries,
+    /// Keys are not sorted or contain a duplicate
+    UnsortedKeys,
+    /// Accounts are not sorted or contain a duplicate
+    UnsortedAccounts,
+    /// Waits are not sorted or contain a duplicate
+    UnsortedWaits,
+    ZeroWaitSec,
+    /// The weights sum up to less than the threshold
+    UnreachableThreshold,
+    /// A weight read from JSON does not fit in `u16`
+    WeightOverflow(u32),
+}
+
+impl From<AuthorityError> for crate::Error {
+    fn from(e: AuthorityError) -> crate::Error {
+        crate::Error::InvalidAuthority(e)
+    }
+}
+
+fn is_strictly_sorted<T>(items: &[T], less: impl Fn(&T, &T) -> bool) -> bool {
+    items.windows(2).all(|pair| less(&pair[0], &pair[1]))
+}
+
+impl Authority {
+    pub fn new(threshold: u32, keys: Vec<KeyWeight>, accounts: Vec<PermissionLevelWeight>, waits: Vec<WaitWeight>) -> Self {
+        Authority { threshold, keys, accounts, waits }
+    }
+
+    /// An authority satisfied by a single key.
+    pub fn from_key(key: PublicKey) -> Self {
+        Authority {
+            threshold: 1,
+            keys: vec![KeyWeight { key, weight: 1 }],
+            accounts: vec![],
+            waits: vec![],
+        }
+    }
+
+    /// An authority satisfied by a single account permission, e.g. `eosio.code`.
+    pub fn from_permission(permission: PermissionLevel) -> Self {
+        Authority {
+            threshold: 1,
+            keys: vec![],
+            accounts: vec![PermissionLevelWeight { permission, weight: 1 }],
+            waits: vec![],
+        }
+    }
+
+    /// Sort keys, accounts and waits into the order nodeos requires.
+    pub fn sort(&mut self) {
+        self.keys.sort_by(|a, b| a.key.cmp(&b.key));
+        self.accounts.sort_by_key(|a| a.permission);
+        self.waits.sort_by_key(|w| w.wait_sec);
+    }
+
+    /// The sum of all weights.
+    pub fn total_weight(&self) -> u64 {
+        let keys: u64 = self.keys.iter().map(|k| u64::from(k.weight)).sum();
+        let accounts: u64 = self.accounts.iter().map(|a| u64::from(a.weight)).sum();
+        let waits: u64 = self.waits.iter().map(|w| u64::from(w.weight)).sum();
+        keys + accounts + waits
+    }
+
+    /// Apply the same checks as `eosio::chain::validate(const authority&)`.
+    pub fn validate(&self) -> Result<(), AuthorityError> {
+        if self.keys.len() + self.accounts.len() + self.waits.len() > 1 << 16 {
+            return Err(AuthorityError::TooManyEntries);
+        }
+        if self.threshold == 0 {
+            return Err(AuthorityError::ZeroThreshold);
+        }
+        if !is_strictly_sorted(&self.keys, |a, b| a.key < b.key) {
+            return Err(AuthorityError::UnsortedKeys);
+        }
+        if !is_strictly_sorted(&self.accounts, |a, b| a.permission < b.permission) {
+            return Err(AuthorityError::UnsortedAccounts);
+        }
+        if self.waits.first().is_some_and(|w| w.wait_sec == 0) {
+            return Err(AuthorityError::ZeroWaitSec);
+        }
+        if !is_strictly_sorted(&self.waits, |a, b| a.wait_sec < b.wait_sec) {
+            return Err(AuthorityError::UnsortedWaits);
+        }
+        if self.total_weight() < u64::from(self.threshold) {
+            return Err(AuthorityError::UnreachableThreshold);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn key(s: &str) -> PublicKey {
+        PublicKey::from_str(s).unwrap()
+    }
+
+    fn multisig() -> Authority {
+        let mut auth = Authority {
+            threshold: 3,
+            keys: vec![
+                KeyWeight { key: key("EOS8FdQ4gt16pFcSiXAYCcHnkHTS2nNLFWGZXW5sioAdvQuMxKhAm"), weight: 1 },
+                KeyWeight { key: key("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"), weight: 1 },
+            ],
+            accounts: vec![
+                PermissionLevelWeight { permission: PermissionLevel::from_str("carol", "active").unwrap(), weight: 1 },
+                PermissionLevelWeight { permission: PermissionLevel::from_str("bob", "owner").unwrap(), weight: 1 },
+                PermissionLevelWeight { permission: PermissionLevel::from_str("bob", "active").unwrap(), weight: 1 },
+            ],
+            waits: vec![
+                WaitWeight { wait_sec: 7200, weight: 1 },
+                WaitWeight { wait_sec: 3600, weight: 1 },
+            ],
+        };
+        auth.sort();
+        auth
+    }
+
+    #[test]
+    fn authority_sort_should_work() {
+        let auth = multisig();
+        assert_eq!(auth.keys[0].key, key("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"));
+        assert_eq!(auth.accounts[0].permission, PermissionLevel::from_str("bob", "active").unwrap());
+        assert_eq!(auth.accounts[1].permission, PermissionLevel::from_str("bob", "owner").unwrap());
+        assert_eq!(auth.waits[0].wait_sec, 3600);
+        assert_eq!(auth.total_weight(), 7);
+        assert_eq!(auth.validate(), Ok(()));
+    }
+
+    #[test]
+    fn authority_validate_should_error() {
+        let mut auth = multisig();
+        auth.keys.swap(0, 1);
+        assert_eq!(auth.validate(), Err(AuthorityError::UnsortedKeys));
+
+        let mut auth = multisig();
+        let duplicate = auth.accounts[0].clone();
+        auth.accounts.insert(0, duplicate);
+        assert_eq!(auth.validate(), Err(AuthorityError::UnsortedAccounts));
+
+        let mut auth = multisig();
+        auth.waits.swap(0, 1);
+        assert_eq!(auth.validate(), Err(AuthorityError::UnsortedWaits));
+
+        let mut auth = multisig();
+        auth.waits[0].wait_sec = 0;
+        assert_eq!(auth.validate(), Err(AuthorityError::ZeroWaitSec));
+
+        // nodeos accepts zero weights as long as the threshold stays reachable
+        let mut auth = multisig();
+        auth.accounts[2].weight = 0;
+        assert_eq!(auth.validate(), Ok(()));
+
+        let mut auth = multisig();
+        auth.threshold = 0;
+        assert_eq!(auth.validate(), Err(AuthorityError::ZeroThreshold));
+
+        let mut auth = multisig();
+        auth.threshold = 8;
+        assert_eq!(auth.validate(), Err(AuthorityError::UnreachableThreshold));
+    }
+
+    #[test]
+    fn authority_serialize_should_work() {
+        let auth = Authority::from_key(key("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"));
+        assert!(auth.is_valid());
+
+        let mut data = vec![0u8; auth.num_bytes()];
+        auth.write(&mut data, &mut 0).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "01000000010002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf01000000"
+        );
+        assert_eq!(Authority::read(&data, &mut 0).unwrap(), auth);
+    }
+
+    #[test]
+    fn authority_deserialize_should_work() {
+        let json = r#"{
+            "threshold": 1,
+            "keys": [{"key": "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV", "weight": 1}],
+            "accounts": [{"permission": {"actor": "bob", "permission": "eosio.code"}, "weight": 1}],
+            "waits": []
+        }"#;
+        let auth: Authority = serde_json::from_str(json).unwrap();
+        assert_eq!(auth.keys[0].key, key("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"));
+        assert_eq!(auth.accounts[0].permission, PermissionLevel::from_str("bob", "eosio.code").unwrap());
+        assert!(auth.is_valid());
+    }
+}
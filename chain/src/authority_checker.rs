@@ -0,0 +1,338 @@
+//! Decide offline whether keys and permissions satisfy an authority.
+//! Mirrors <https://github.com/EOSIO/eos/blob/master/libraries/chain/include/eosio/chain/authority_checker.hpp>
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{Authority, PermissionLevel, PermissionName, PublicKey};
+
+/// The default `max_authority_depth` of nodeos.
+pub const MAX_AUTHORITY_DEPTH: u16 = 6;
+
+/// `eosio.any`, a provided `actor@eosio.any` satisfies every permission of `actor`,
+/// and a link to it lets any permission of the account authorize the action.
+pub(crate) const EOSIO_ANY: u64 = 6_138_663_581_382_606_848;
+
+/// Where the checker looks up the authority of a permission.
+pub trait PermissionStore {
+    fn get_authority(&self, permission: &PermissionLevel) -> Option<&Authority>;
+}
+
+impl PermissionStore for BTreeMap<PermissionLevel, Authority> {
+    fn get_authority(&self, permission: &PermissionLevel) -> Option<&Authority> {
+        self.get(permission)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PermissionStatus {
+    BeingEvaluated,
+    Satisfied,
+    Unsatisfied,
+}
+
+/// A weight to tally, in the order nodeos checks them.
+enum MetaPermission<'a> {
+    Wait(u32, u16),
+    Key(&'a PublicKey, u16),
+    Account(&'a PermissionLevel, u16),
+}
+
+impl MetaPermission<'_> {
+    fn weight(&self) -> u16 {
+        match *self {
+            MetaPermission::Wait(_, weight)
+            | MetaPermission::Key(_, weight)
+            | MetaPermission::Account(_, weight) => weight,
+        }
+    }
+}
+
+/// Checks permissions against the provided keys, permissions and delay.
+///
+/// Keys are only marked as used when the authority they belong to ends up
+/// satisfied, so after a successful check `used_keys` is the key set to sign with.
+pub struct AuthorityChecker<'a, S> {
+    store: &'a S,
+    provided_keys: Vec<PublicKey>,
+    used_keys: Vec<bool>,
+    provided_permissions: Vec<PermissionLevel>,
+    provided_delay_sec: u32,
+    max_depth: u16,
+}
+
+impl<'a, S: PermissionStore> AuthorityChecker<'a, S> {
+    pub fn new<K, P>(store: &'a S, provided_keys: K, provided_permissions: P) -> Self
+        where K: IntoIterator<Item=PublicKey>, P: IntoIterator<Item=PermissionLevel>
+    {
+        let mut provided_keys: Vec<_> = provided_keys.into_iter().collect();
+        provided_keys.sort();
+        provided_keys.dedup();
+        let used_keys = alloc::vec![false; provided_keys.len()];
+
+        AuthorityChecker {
+            store,
+            provided_keys,
+            used_keys,
+            provided_permissions: provided_permissions.into_iter().collect(),
+            provided_delay_sec: 0,
+            max_depth: MAX_AUTHORITY_DEPTH,
+        }
+    }
+
+    /// Delay the transaction is sent with, it satisfies waits up to this long.
+    pub fn provided_delay_sec(mut self, delay_sec: u32) -> Self {
+        self.provided_delay_sec = delay_sec;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Check whether `permission` is satisfied.
+    pub fn satisfied(&mut self, permission: &PermissionLevel) -> bool {
+        let mut cache = self.initial_cache();
+        self.tally_permission(permission, 1, &mut cache, 0) > 0
+    }
+
+    /// Check whether an authority is satisfied, its account weights are resolved through the store.
+    pub fn satisfied_authority(&mut self, authority: &Authority) -> bool {
+        let mut cache = self.initial_cache();
+        self.satisfied_with_cache(authority, &mut cache, 0)
+    }
+
+    pub fn used_keys(&self) -> Vec<PublicKey> {
+        self.keys_where(true)
+    }
+
+    pub fn unused_keys(&self) -> Vec<PublicKey> {
+        self.keys_where(false)
+    }
+
+    /// Whether every provided key contributed, nodeos rejects transactions with irrelevant signatures.
+    pub fn all_keys_used(&self) -> bool {
+        self.used_keys.iter().all(|used| *used)
+    }
+
+    fn keys_where(&self, used: bool) -> Vec<PublicKey> {
+        self.provided_keys.iter().zip(self.used_keys.iter())
+            .filter(|(_, u)| **u == used)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn initial_cache(&self) -> BTreeMap<PermissionLevel, PermissionStatus> {
+        self.provided_permissions.iter()
+            .map(|p| (*p, PermissionStatus::Satisfied))
+            .collect()
+    }
+
+    fn status_in_cache(
+        cache: &BTreeMap<PermissionLevel, PermissionStatus>,
+        level: &PermissionLevel,
+    ) -> Option<PermissionStatus> {
+        let any = PermissionLevel::new(level.actor, PermissionName::from(EOSIO_ANY));
+        cache.get(level).or_else(|| cache.get(&any)).cloned()
+    }
+
+    fn satisfied_with_cache(
+        &mut self,
+        authority: &Authority,
+        cache: &mut BTreeMap<PermissionLevel, PermissionStatus>,
+        depth: u16,
+    ) -> bool {
+        // keys used by an authority that ends up unsatisfied don't count as used
+        let used_keys = self.used_keys.clone();
+
+        let mut permissions: Vec<MetaPermission> = Vec::with_capacity(
+            authority.waits.len() + authority.keys.len() + authority.accounts.len()
+        );
+        permissions.extend(authority.waits.iter().map(|w| MetaPermission::Wait(w.wait_sec, w.weight)));
+        permissions.extend(authority.keys.iter().map(|k| MetaPermission::Key(&k.key, k.weight)));
+        permissions.extend(authority.accounts.iter().map(|a| MetaPermission::Account(&a.permission, a.weight)));
+        // heaviest first, the sort is stable so waits come before keys before accounts on ties
+        permissions.sort_by_key(|p| core::cmp::Reverse(p.weight()));
+
+        let threshold = authority.threshold;
+        let mut total_weight = 0u32;
+        for permission in permissions.iter() {
+            total_weight = match *permission {
+                MetaPermission::Wait(wait_sec, weight) => {
+                    if self.provided_delay_sec >= wait_sec {
+                        total_weight + u32::from(weight)
+                    } else {
+                        total_weight
+                    }
+                }
+                MetaPermission::Key(key, weight) => {
+                    match self.provided_keys.binary_search(key) {
+                        Ok(pos) => {
+                            self.used_keys[pos] = true;
+                            total_weight + u32::from(weight)
+                        }
+                        Err(_) => total_weight,
+                    }
+                }
+                MetaPermission::Account(level, weight) => {
+                    total_weight + self.tally_permission(level, weight, cache, depth)
+                }
+            };
+            if total_weight >= threshold {
+                return true;
+            }
+        }
+
+        self.used_keys = used_keys;
+        false
+    }
+
+    fn tally_permission(
+        &mut self,
+        level: &PermissionLevel,
+        weight: u16,
+        cache: &mut BTreeMap<PermissionLevel, PermissionStatus>,
+        depth: u16,
+    ) -> u32 {
+        match Self::status_in_cache(cache, level) {
+            Some(PermissionStatus::Satisfied) => u32::from(weight),
+            Some(_) => 0,
+            None if depth < self.max_depth => {
+                // a permission that doesn't exist is skipped
+                let store = self.store;
+                let authority = match store.get_authority(level) {
+                    Some(authority) => authority,
+                    None => return 0,
+                };
+                cache.insert(*level, PermissionStatus::BeingEvaluated);
+                if self.satisfied_with_cache(authority, cache, depth + 1) {
+                    cache.insert(*level, PermissionStatus::Satisfied);
+                    u32::from(weight)
+                } else {
+                    cache.insert(*level, PermissionStatus::Unsatisfied);
+                    0
+                }
+            }
+            None => 0,
+        }
+    }
+}
+
+/// The subset of `candidate_keys` needed to satisfy `permission`, like the
+/// `get_required_keys` RPC. `None` if the candidates can't satisfy it.
+pub fn required_keys<S, K>(store: &S, permission: &PermissionLevel, candidate_keys: K) -> Option<Vec<PublicKey>>
+    where S: PermissionStore, K: IntoIterator<Item=PublicKey>
+{
+    let mut checker = AuthorityChecker::new(store, candidate_keys, None);
+    if checker.satisfied(permission) {
+        Some(checker.used_keys())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::str::FromStr;
+    use crate::{KeyWeight, PermissionLevelWeight, WaitWeight};
+
+    const KEY_A: &str = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+    const KEY_B: &str = "EOS8FdQ4gt16pFcSiXAYCcHnkHTS2nNLFWGZXW5sioAdvQuMxKhAm";
+    const KEY_C: &str = "EOS55KuLPN3u9qii2hEhJhkdQSdaVLVPTHdwdkEhszhhCWDthQtfi";
+
+    fn key(s: &str) -> PublicKey {
+        PublicKey::from_str(s).unwrap()
+    }
+
+    fn level(actor: &str, permission: &str) -> PermissionLevel {
+        PermissionLevel::from_str(actor, permission).unwrap()
+    }
+
+    fn key_weight(s: &str, weight: u16) -> KeyWeight {
+        KeyWeight { key: key(s), weight }
+    }
+
+    fn account_weight(actor: &str, permission: &str, weight: u16) -> PermissionLevelWeight {
+        PermissionLevelWeight { permission: level(actor, permission), weight }
+    }
+
+    /// alice@active: 2 of (KEY_A, KEY_B, bob@active, 1h wait)
+    /// bob@active: KEY_C
+    /// carol@active <-> dave@active reference each other
+    fn store() -> BTreeMap<PermissionLevel, Authority> {
+        let mut store = BTreeMap::new();
+        store.insert(level("alice", "active"), Authority {
+            threshold: 2,
+            keys: vec![key_weight(KEY_A, 1), key_weight(KEY_B, 1)],
+            accounts: vec![account_weight("bob", "active", 1)],
+            waits: vec![WaitWeight { wait_sec: 3600, weight: 1 }],
+        });
+        store.insert(level("bob", "active"), Authority::from_key(key(KEY_C)));
+        store.insert(level("carol", "active"), Authority::from_permission(level("dave", "active")));
+        store.insert(level("dave", "active"), Authority::from_permission(level("carol", "active")));
+        store
+    }
+
+    #[test]
+    fn checker_keys_should_work() {
+        let store = store();
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A), key(KEY_B)], None);
+        assert!(checker.satisfied(&level("alice", "active")));
+        assert!(checker.all_keys_used());
+
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A)], None);
+        assert!(!checker.satisfied(&level("alice", "active")));
+        // the key of an unsatisfied authority is not used
+        assert!(checker.used_keys().is_empty());
+    }
+
+    #[test]
+    fn checker_accounts_should_work() {
+        let store = store();
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A), key(KEY_C)], None);
+        assert!(checker.satisfied(&level("alice", "active")));
+        assert_eq!(checker.used_keys().len(), 2);
+
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A)], vec![level("bob", "active")]);
+        assert!(checker.satisfied(&level("alice", "active")));
+
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_B)], vec![level("bob", "eosio.any")]);
+        assert!(checker.satisfied(&level("alice", "active")));
+    }
+
+    #[test]
+    fn checker_waits_should_work() {
+        let store = store();
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A)], None).provided_delay_sec(3600);
+        assert!(checker.satisfied(&level("alice", "active")));
+
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A)], None).provided_delay_sec(3599);
+        assert!(!checker.satisfied(&level("alice", "active")));
+    }
+
+    #[test]
+    fn checker_should_stop_at_cycles_and_depth() {
+        let store = store();
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_A)], None);
+        assert!(!checker.satisfied(&level("carol", "active")));
+
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_C)], None).max_depth(1);
+        assert!(!checker.satisfied(&level("alice", "active")));
+        let mut checker = AuthorityChecker::new(&store, vec![key(KEY_C)], None).max_depth(1);
+        assert!(checker.satisfied(&level("bob", "active")));
+    }
+
+    #[test]
+    fn required_keys_should_work() {
+        let store = store();
+        let candidates = vec![key(KEY_A), key(KEY_B), key(KEY_C)];
+        let required = required_keys(&store, &level("bob", "active"), candidates.clone());
+        assert_eq!(required, Some(vec![key(KEY_C)]));
+
+        let required = required_keys(&store, &level("alice", "active"), candidates).unwrap();
+        assert_eq!(required.len(), 2);
+        assert!(required_keys(&store, &level("alice", "active"), vec![key(KEY_C)]).is_none());
+        assert!(required_keys(&store, &level("nobody", "active"), vec![key(KEY_C)]).is_none());
+    }
+}
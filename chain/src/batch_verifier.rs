@@ -0,0 +1,229 @@
+//! Verify many (digest, signature, public key) triples at once.
+//!
+//! `PublicKey::verify` parses the key on every call, decompressing the curve
+//! point is most of that cost, while a block only carries signatures from a
+//! handful of producer and account keys. `BatchVerifier` parses each distinct key
+//! once into a `KeyCache` and can spread the checks over several threads.
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{Checksum256, PublicKey, Signature};
+
+/// Parsed secp256k1 public keys, indexed by their compressed bytes.
+#[derive(Clone, Debug, Default)]
+pub struct KeyCache {
+    index: BTreeMap<[u8; 33], usize>,
+    keys: Vec<keys::public::PublicKey>,
+}
+
+impl KeyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the position of the parsed key, parsing it on first use.
+    pub fn insert(&mut self, pk: &PublicKey) -> crate::Result<usize> {
+        if let Some(pos) = self.index.get(&pk.data) {
+            return Ok(*pos);
+        }
+
+        let key = keys::public::PublicKey::from_slice(&pk.data).map_err(crate::Error::Keys)?;
+        let pos = self.keys.len();
+        self.keys.push(key);
+        self.index.insert(pk.data, pos);
+        Ok(pos)
+    }
+
+    pub fn get(&self, pk: &PublicKey) -> Option<&keys::public::PublicKey> {
+        self.index.get(&pk.data).map(|pos| &self.keys[*pos])
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    digest: Checksum256,
+    signature: keys::signature::Signature,
+    key: usize,
+}
+
+/// A queue of signatures to verify, the parsed keys are kept across `clear`.
+#[derive(Clone, Debug, Default)]
+pub struct BatchVerifier {
+    cache: KeyCache,
+    entries: Vec<Entry>,
+}
+
+impl BatchVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a verifier reusing keys parsed by a previous one.
+    pub fn with_cache(cache: KeyCache) -> Self {
+        BatchVerifier { cache, entries: Vec::new() }
+    }
+
+    /// Queue a triple, fails right away if the key or the signature can't be parsed.
+    pub fn push(&mut self, digest: Checksum256, signature: &Signature, pk: &PublicKey) -> crate::Result<()> {
+        let signature = keys::signature::Signature::from_compact(&signature.data).map_err(crate::Error::Keys)?;
+        let key = self.cache.insert(pk)?;
+        self.entries.push(Entry { digest, signature, key });
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn cache(&self) -> &KeyCache {
+        &self.cache
+    }
+
+    /// Drop the queued triples and keep the parsed keys.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn into_cache(self) -> KeyCache {
+        self.cache
+    }
+
+    fn verify_entry(&self, entry: &Entry) -> bool {
+        self.cache.keys[entry.key].verify_hash(entry.digest.as_bytes(), &entry.signature).is_ok()
+    }
+
+    fn first_failure(&self, entries: &[Entry]) -> Option<usize> {
+        entries.iter().position(|entry| !self.verify_entry(entry))
+    }
+
+    /// Verify all queued triples, the error carries the position of the first invalid one.
+    pub fn verify(&self) -> crate::Result<()> {
+        match self.first_failure(&self.entries) {
+            Some(pos) => Err(crate::Error::BatchVerificationError(pos)),
+            None => Ok(()),
+        }
+    }
+
+    /// Verify all queued triples and report the result of each one.
+    pub fn verify_each(&self) -> Vec<bool> {
+        self.entries.iter().map(|entry| self.verify_entry(entry)).collect()
+    }
+
+    /// Same as `verify`, with the triples split over `threads` threads.
+    #[cfg(feature = "std")]
+    pub fn verify_parallel(&self, threads: usize) -> crate::Result<()> {
+        let threads = threads.max(1);
+        if threads == 1 || self.entries.len() < 2 {
+            return self.verify();
+        }
+
+        let chunk_size = self.entries.len().div_ceil(threads);
+        let first_failure = std::thread::scope(|scope| {
+            let workers: Vec<_> = self.entries.chunks(chunk_size).enumerate()
+                .map(|(i, chunk)| scope.spawn(move || {
+                    self.first_failure(chunk).map(|pos| i * chunk_size + pos)
+                }))
+                .collect();
+            workers.into_iter()
+                .filter_map(|worker| worker.join().expect("verification thread panicked"))
+                .min()
+        });
+
+        match first_failure {
+            Some(pos) => Err(crate::Error::BatchVerificationError(pos)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use keys::secret::SecretKey;
+
+    fn secret_keys() -> Vec<SecretKey> {
+        vec![
+            "5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3",
+            "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4",
+            "5KJVA9P4xsiRC3zPy1KPa3GA6ffvmyZSxhKPbE924YJphvSCG4F",
+        ].into_iter().map(|wif| SecretKey::from_wif(wif).unwrap()).collect()
+    }
+
+    fn signed_triples(count: usize) -> Vec<(Checksum256, Signature, PublicKey)> {
+        let sks = secret_keys();
+        (0..count).map(|i| {
+            let sk = &sks[i % sks.len()];
+            let digest = Checksum256::hash_from_slice(&(i as u64).to_le_bytes());
+            let signature = sk.sign_hash(digest.as_bytes()).unwrap().into();
+            let pk: PublicKey = keys::public::PublicKey::from(sk).into();
+            (digest, signature, pk)
+        }).collect()
+    }
+
+    fn batch(triples: &[(Checksum256, Signature, PublicKey)]) -> BatchVerifier {
+        let mut verifier = BatchVerifier::new();
+        for (digest, signature, pk) in triples {
+            verifier.push(*digest, signature, pk).unwrap();
+        }
+        verifier
+    }
+
+    #[test]
+    fn batch_verify_should_work() {
+        let triples = signed_triples(10);
+        for (digest, signature, pk) in triples.iter() {
+            assert!(pk.verify(digest.as_bytes(), signature).is_ok());
+        }
+
+        let verifier = batch(&triples);
+        assert_eq!(verifier.len(), 10);
+        assert_eq!(verifier.cache().len(), 3);
+        assert!(verifier.verify().is_ok());
+        assert_eq!(verifier.verify_each(), vec![true; 10]);
+        assert!(verifier.verify_parallel(4).is_ok());
+    }
+
+    #[test]
+    fn batch_verify_should_report_first_failure() {
+        let mut triples = signed_triples(10);
+        triples[7].0 = Checksum256::hash_from_slice(b"tampered");
+        triples[3].2 = triples[4].2.clone();
+
+        let verifier = batch(&triples);
+        match verifier.verify() {
+            Err(crate::Error::BatchVerificationError(pos)) => assert_eq!(pos, 3),
+            _ => panic!("batch verification should fail"),
+        }
+        match verifier.verify_parallel(3) {
+            Err(crate::Error::BatchVerificationError(pos)) => assert_eq!(pos, 3),
+            _ => panic!("parallel batch verification should fail"),
+        }
+        let results = verifier.verify_each();
+        assert_eq!(results.iter().filter(|ok| !**ok).count(), 2);
+        assert!(!results[3] && !results[7]);
+    }
+
+    #[test]
+    fn batch_verifier_should_keep_cache() {
+        let triples = signed_triples(6);
+        let mut verifier = batch(&triples);
+        verifier.clear();
+        assert!(verifier.is_empty());
+        assert_eq!(verifier.cache().len(), 3);
+
+        let verifier = BatchVerifier::with_cache(verifier.into_cache());
+        assert!(verifier.cache().get(&triples[0].2).is_some());
+    }
+}
@@ -6,12 +6,15 @@ use crate::{
     ActionName,
     AccountName,
     ActionTransfer,
+    BlockExtension,
     Checksum256,
     Extension,
+    merkle,
     NumBytes,
     PackedTransaction,
     Read,
     SerializeData,
+    Signature,
     SignedBlockHeader,
     Transaction,
     UnsignedInt,
@@ -49,6 +52,43 @@ impl SignedBlock {
     pub fn block_num(&self) -> u32 {
         self.signed_block_header.block_num()
     }
+
+    /// The merkle root of the transaction receipt digests, what nodeos puts in
+    /// `transaction_mroot`.
+    pub fn compute_transaction_mroot(&self) -> crate::Result<Checksum256> {
+        let digests = self.transactions.iter()
+            .map(TransactionReceipt::digest)
+            .collect::<crate::Result<Vec<Checksum256>>>()?;
+        merkle(digests)
+    }
+
+    /// Check the transactions and extensions of a block, e.g. one fetched from an
+    /// untrusted node, match its header.
+    pub fn validate(&self) -> crate::Result<()> {
+        let header = &self.signed_block_header.block_header;
+        if self.compute_transaction_mroot()? != header.transaction_mroot {
+            return Err(crate::Error::InvalidTransactionMroot);
+        }
+        header.validate_and_extract_header_extensions()?;
+        self.validate_and_extract_extensions()?;
+        Ok(())
+    }
+
+    /// Decode `block_extensions`, unknown extensions are kept as they are.
+    pub fn validate_and_extract_extensions(&self) -> crate::Result<Vec<BlockExtension>> {
+        BlockExtension::validate_and_extract(&self.block_extensions)
+    }
+
+    /// The signatures of the `additional_block_signatures` extension, empty for a
+    /// block signed by a single key.
+    pub fn additional_signatures(&self) -> crate::Result<Vec<Signature>> {
+        let signatures = self.validate_and_extract_extensions()?.into_iter()
+            .find_map(|ext| match ext {
+                BlockExtension::AdditionalBlockSignatures(signatures) => Some(signatures),
+                _ => None,
+            });
+        Ok(signatures.unwrap_or_default())
+    }
 }
 
 impl core::fmt::Display for SignedBlock {
@@ -397,13 +437,58 @@ mod tests {
         let mut pos = 0;
         let block = SignedBlock::read(&data.as_slice(), &mut pos).unwrap();
 
-        let trxs: Vec<TransactionReceipt> = block.transactions;
+        let trxs: Vec<TransactionReceipt> = block.transactions.clone();
         let mut trxs_digests: Vec<Checksum256> = Vec::new();
         for trx in trxs {
             trxs_digests.push(trx.digest().unwrap());
         }
         let merkle_root = merkle(trxs_digests.clone()).unwrap();
         assert_eq!(merkle_root, block.signed_block_header.block_header.transaction_mroot);
+        assert_eq!(block.compute_transaction_mroot().unwrap(), merkle_root);
+        assert!(block.validate().is_ok());
+    }
+
+    #[test]
+    fn tampered_block_should_not_validate() {
+        let data = hex::decode("0f57684a0000000000ea3055000000077cb6d5534a23579751f578148b8f0f2da54cd22243b4d6c17ba398ab8a900096714e43362a3bf531eaf43114603689e5561a36aa08225329eca7d939d22049b91659d7073782d1c456a29dde5ace92dffde0cfa78bb284e8d4d7f976fda1000000000000001f36f6f52520fa593f567826935186688d6bb6de7938ec8102c7f726bafe7cc8ae2b5585a3c8ee3a1e79011726b77a2b5f9a0593391ce7fc42c42b2e4a43cc011001005301000010010100206b22f146d8bfe03a7a03b760cb2539409b05f9961543ee41c31f0cf493267b8c244d1517a6aa67cf47f294755d9e2fb5dda6779f5d88d6e4461f380a2b02964b000053256fa15db57c56c88ddb000000000100a6823403ea3055000000572d3ccdcd010000000000855c3400000000a8ed3232210000000000855c340000000000000e3d102700000000000004454f5300000000000000").unwrap();
+        let block = SignedBlock::read(data.as_slice(), &mut 0).unwrap();
+
+        let mut tampered = block.clone();
+        tampered.transactions[0].trx_receipt_header.cpu_usage_us += 1;
+        match tampered.validate() {
+            Err(crate::Error::InvalidTransactionMroot) => {}
+            _ => panic!("a tampered receipt should not validate"),
+        }
+
+        let mut dropped = block.clone();
+        dropped.transactions.clear();
+        assert_eq!(dropped.compute_transaction_mroot().unwrap(), Checksum256::default());
+        assert!(dropped.validate().is_err());
+
+        let mut reordered = block;
+        reordered.block_extensions = vec![Extension(3, vec![]), Extension(2, vec![])];
+        match reordered.validate() {
+            Err(crate::Error::ExtensionError(crate::ExtensionError::NotInOrder(2))) => {}
+            _ => panic!("extensions out of order should not validate"),
+        }
+    }
+
+    #[test]
+    fn block_additional_signatures_should_work() {
+        let mut block = SignedBlock::default();
+        assert!(block.additional_signatures().unwrap().is_empty());
+
+        let sk = keys::secret::SecretKey::from_wif(crate::test_utils::WIFS[0]).unwrap();
+        let sig: Signature = sk.sign_hash(&[1u8; 32]).unwrap().into();
+        let ext = BlockExtension::AdditionalBlockSignatures(vec![sig.clone()]).to_extension().unwrap();
+        block.block_extensions = vec![ext, Extension(3, vec![1])];
+        let signatures = block.additional_signatures().unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].to_bytes()[..], sig.to_bytes()[..]);
+        assert_eq!(block.validate_and_extract_extensions().unwrap()[1], BlockExtension::Unknown(Extension(3, vec![1])));
+
+        block.block_extensions = vec![Extension(2, vec![1u8, 0])];
+        assert!(block.additional_signatures().is_err());
     }
 
     #[test]
@@ -2,8 +2,8 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use core::{convert::From, str::FromStr};
 use crate::{
-    AccountName, utils::bitutil, BlockTimestamp,
-    Checksum256, Extension, NumBytes, ProducerSchedule,
+    AccountName, utils::bitutil, BlockHeaderStateError, BlockSigningAuthority, BlockTimestamp,
+    Checksum256, Extension, HeaderExtension, NumBytes, ProducerAuthoritySchedule, ProducerSchedule,
     Read, Signature, Write, PublicKey, TimePoint, SerializeData
 };
 use codec::{Encode, Decode};
@@ -99,6 +99,26 @@ impl BlockHeader {
     pub fn num_from_id(id: Checksum256) -> u32 {
         bitutil::endian_reverse_u32(id.hash0() as u32)
     }
+
+    /// Decode `header_extensions`, unknown extensions are kept as they are.
+    pub fn validate_and_extract_header_extensions(&self) -> crate::Result<Vec<HeaderExtension>> {
+        HeaderExtension::validate_and_extract(&self.header_extensions)
+    }
+
+    /// The schedule proposed by the header, either legacy `new_producers` converted to
+    /// single key authorities or the `producer_schedule_change_extension` of EOSIO 2.0.
+    pub fn new_producer_schedule(&self) -> crate::Result<Option<ProducerAuthoritySchedule>> {
+        let schedule_change = self.validate_and_extract_header_extensions()?.into_iter()
+            .find_map(|ext| match ext {
+                HeaderExtension::ProducerScheduleChange(schedule) => Some(schedule),
+                _ => None,
+            });
+        match (&self.new_producers, schedule_change) {
+            (Some(_), Some(_)) => Err(BlockHeaderStateError::DuplicateScheduleChange.into()),
+            (Some(legacy), None) => Ok(Some(ProducerAuthoritySchedule::from(legacy.clone()))),
+            (None, schedule) => Ok(schedule),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Read, Write, NumBytes, PartialEq, Encode, Decode, SerializeData)]
@@ -129,6 +149,22 @@ impl SignedBlockHeader {
         pk.verify(digest.as_bytes(), &self.producer_signature)
     }
 
+    /// Verify a block signed by several keys against the producer authority,
+    /// `additional_signatures` are the ones of its `additional_block_signatures` extension.
+    pub fn verify_signatures(
+        &self,
+        blockroot_merkle: Checksum256,
+        schedule_hash: Checksum256,
+        authority: &BlockSigningAuthority,
+        additional_signatures: &[Signature],
+    ) -> crate::Result<()> {
+        let digest = self.sig_digest(blockroot_merkle, schedule_hash)?;
+        let mut signatures = Vec::with_capacity(additional_signatures.len() + 1);
+        signatures.push(self.producer_signature.clone());
+        signatures.extend_from_slice(additional_signatures);
+        authority.verify_signatures(&digest, &signatures)
+    }
+
     fn sig_digest(&self, blockroot_merkle: Checksum256, schedule_hash: Checksum256) -> crate::Result<Checksum256> {
         let block_header_hash = self.block_header.digest()?;
         let header_bmroot = Checksum256::hash((block_header_hash, blockroot_merkle))?;
@@ -320,4 +356,26 @@ mod test {
         let serialized_signed_block_header = serde_json::to_string(&signed_block.unwrap());
         assert!(serialized_signed_block_header.is_ok());
     }
+
+    #[test]
+    fn new_producer_schedule_should_work() {
+        let json = "signed_block_header.json";
+        let signed_block_header: SignedBlockHeader = serde_json::from_str(&read_json_from_file(json).unwrap()).unwrap();
+        let mut header = signed_block_header.block_header;
+        let legacy = header.new_producers.clone().unwrap();
+        let schedule = ProducerAuthoritySchedule::from(legacy.clone());
+        assert_eq!(header.new_producer_schedule().unwrap(), Some(schedule.clone()));
+
+        let extension = HeaderExtension::ProducerScheduleChange(schedule.clone()).to_extension().unwrap();
+        header.header_extensions.push(extension);
+        match header.new_producer_schedule() {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::DuplicateScheduleChange)) => {}
+            r => panic!("expect a duplicate schedule change, got {:?}", r),
+        }
+
+        header.new_producers = None;
+        assert_eq!(header.new_producer_schedule().unwrap(), Some(schedule));
+        header.header_extensions.clear();
+        assert_eq!(header.new_producer_schedule().unwrap(), None);
+    }
 }
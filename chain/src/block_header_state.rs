@@ -0,0 +1,456 @@
+//! Track the state nodeos derives from each block header: the blockroot merkle,
+//! producer schedule promotion and the last irreversible block.
+//! <https://github.com/EOSIO/eos/blob/master/libraries/chain/block_header_state.cpp>
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{
+    AccountName, BlockHeader, BlockSigningAuthority, BlockTimestamp, Checksum256,
+    IncrementalMerkle, NumBytes, ProducerAuthority, ProducerAuthoritySchedule, ProducerSchedule,
+    Read, Signature, SignedBlock, SignedBlockHeader, Write
+};
+
+/// Blocks waiting for confirmations that are tracked at most.
+pub const MAXIMUM_TRACKED_DPOS_CONFIRMATIONS: usize = 1024;
+/// Producers a schedule has at most, `config::max_producers` of nodeos.
+pub const MAX_PRODUCERS: usize = 125;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockHeaderStateError {
+    /// The timestamp is not after the one of the previous block
+    TimestampNotIncreasing,
+    /// `previous` is not the id of the state the header is applied to
+    Unlinkable,
+    /// Another header with this block number was already applied
+    Fork(u32),
+    /// The header is not produced by the producer scheduled for its slot
+    WrongProducer(AccountName),
+    /// The producer confirms blocks it already confirmed
+    DoubleConfirm(AccountName),
+    /// `schedule_version` is not the version of the active schedule
+    WrongScheduleVersion(u32),
+    /// A proposed schedule is not the next version of the active schedule
+    WrongNewScheduleVersion(u32),
+    /// A schedule is proposed before the pending one is promoted
+    PendingScheduleNotPromoted,
+    /// A schedule is proposed in the block that promotes the pending one
+    ScheduleChangeOnPromotion,
+    /// Both `new_producers` and a schedule change extension are set
+    DuplicateScheduleChange,
+    EmptySchedule,
+    /// A schedule has more than `MAX_PRODUCERS` producers
+    TooManyProducers,
+    /// The block signatures don't satisfy the block signing authority
+    InvalidSignature,
+    /// A block carries more signatures than its signing authority has keys
+    TooManySignatures,
+    /// A block carries the same signature twice
+    DuplicateSignature,
+    /// Two signatures of a block are made by the same key
+    DuplicateSigningKey,
+    /// A block signature is made by a key outside the block signing authority
+    UnknownSigningKey,
+}
+
+impl From<BlockHeaderStateError> for crate::Error {
+    fn from(e: BlockHeaderStateError) -> crate::Error {
+        crate::Error::BlockHeaderStateError(e)
+    }
+}
+
+/// A proposed schedule waiting to become active.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ScheduleInfo {
+    /// The block that proposed the schedule, it is promoted once this block is irreversible
+    pub schedule_lib_num: u32,
+    pub schedule_hash: Checksum256,
+    pub schedule: ProducerAuthoritySchedule,
+}
+
+/// The state after applying a block header, like `block_header_state` of nodeos.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockHeaderState {
+    pub id: Checksum256,
+    pub block_num: u32,
+    pub header: SignedBlockHeader,
+    pub dpos_proposed_irreversible_blocknum: u32,
+    pub dpos_irreversible_blocknum: u32,
+    pub active_schedule: ProducerAuthoritySchedule,
+    pub pending_schedule: ScheduleInfo,
+    /// Merkle of the ids of all blocks before this one
+    pub blockroot_merkle: IncrementalMerkle,
+    pub producer_to_last_produced: BTreeMap<AccountName, u32>,
+    pub producer_to_last_implied_irb: BTreeMap<AccountName, u32>,
+    pub valid_block_signing_authority: BlockSigningAuthority,
+    /// Signatures of the `additional_block_signatures` block extension
+    pub additional_signatures: Vec<Signature>,
+    /// Confirmations still required by the blocks after the proposed irreversible block
+    pub confirm_count: Vec<u8>,
+}
+
+impl BlockHeaderState {
+    /// The state of the genesis block, `header.action_mroot` carries the chain id.
+    pub fn genesis(header: BlockHeader, initial_schedule: ProducerSchedule) -> crate::Result<Self> {
+        let schedule_hash = initial_schedule.schedule_hash()?;
+        let active_schedule = ProducerAuthoritySchedule::from(initial_schedule);
+        let valid_block_signing_authority = active_schedule.producers.first()
+            .map(|producer| producer.authority.clone())
+            .ok_or(BlockHeaderStateError::EmptySchedule)?;
+        let header = SignedBlockHeader::new(header, Default::default());
+
+        Ok(BlockHeaderState {
+            id: header.id()?,
+            block_num: header.block_num(),
+            header,
+            dpos_proposed_irreversible_blocknum: 0,
+            dpos_irreversible_blocknum: 0,
+            pending_schedule: ScheduleInfo {
+                schedule_lib_num: 0,
+                schedule_hash,
+                schedule: active_schedule.clone(),
+            },
+            active_schedule,
+            blockroot_merkle: IncrementalMerkle::default(),
+            producer_to_last_produced: BTreeMap::new(),
+            producer_to_last_implied_irb: BTreeMap::new(),
+            valid_block_signing_authority,
+            additional_signatures: Vec::new(),
+            confirm_count: Vec::new(),
+        })
+    }
+
+    /// The producer of the active schedule scheduled for slot `timestamp`.
+    pub fn get_scheduled_producer(&self, timestamp: BlockTimestamp) -> Option<&ProducerAuthority> {
+        self.active_schedule.get_scheduled_producer(timestamp)
+    }
+
+    /// The block two thirds of the producers consider irreversible, taking the
+    /// producer of the next block as having confirmed up to our proposed irreversible block.
+    pub fn calc_dpos_last_irreversible(&self, producer_of_next_block: AccountName) -> u32 {
+        let mut blocknums: Vec<u32> = self.producer_to_last_implied_irb.iter()
+            .map(|(producer, blocknum)| {
+                if *producer == producer_of_next_block {
+                    self.dpos_proposed_irreversible_blocknum
+                } else {
+                    *blocknum
+                }
+            })
+            .collect();
+        if blocknums.is_empty() {
+            return 0;
+        }
+
+        // 2/3 must be greater, so going 1/3 into the list sorted from low to high, 2/3 are greater
+        blocknums.sort_unstable();
+        blocknums[(blocknums.len() - 1) / 3]
+    }
+
+    /// Apply a signed header and verify its producer signature.
+    pub fn next(&self, header: &SignedBlockHeader) -> crate::Result<Self> {
+        self.next_with_signatures(header, Vec::new())
+    }
+
+    /// Apply a signed header of a block signed by several keys, `additional_signatures`
+    /// are the ones of its `additional_block_signatures` extension.
+    pub fn next_with_signatures(
+        &self,
+        header: &SignedBlockHeader,
+        additional_signatures: Vec<Signature>,
+    ) -> crate::Result<Self> {
+        let mut state = self.next_unverified(header)?;
+        state.additional_signatures = additional_signatures;
+        state.verify_signee()?;
+        Ok(state)
+    }
+
+    /// Apply the header of a block and verify all its signatures.
+    pub fn next_block(&self, block: &SignedBlock) -> crate::Result<Self> {
+        self.next_with_signatures(&block.signed_block_header, block.additional_signatures()?)
+    }
+
+    /// Apply a header without checking the producer signature, `sig_digest` of the
+    /// result is what the producer signs.
+    pub fn next_unverified(&self, header: &SignedBlockHeader) -> crate::Result<Self> {
+        let h = &header.block_header;
+        if h.timestamp <= self.header.block_header.timestamp {
+            return Err(BlockHeaderStateError::TimestampNotIncreasing.into());
+        }
+        if h.previous != self.id {
+            return Err(BlockHeaderStateError::Unlinkable.into());
+        }
+        let proauth = self.get_scheduled_producer(h.timestamp)
+            .ok_or(BlockHeaderStateError::EmptySchedule)?;
+        if h.producer != proauth.producer_name {
+            return Err(BlockHeaderStateError::WrongProducer(h.producer).into());
+        }
+        if let Some(last_produced) = self.producer_to_last_produced.get(&proauth.producer_name) {
+            if *last_produced >= (self.block_num + 1).wrapping_sub(u32::from(h.confirmed)) {
+                return Err(BlockHeaderStateError::DoubleConfirm(h.producer).into());
+            }
+        }
+        if h.schedule_version != self.active_schedule.version {
+            return Err(BlockHeaderStateError::WrongScheduleVersion(h.schedule_version).into());
+        }
+
+        let block_num = self.block_num + 1;
+        let mut blockroot_merkle = self.blockroot_merkle.clone();
+        blockroot_merkle.append(self.id)?;
+
+        // the previous active schedule signs and therefore confirms this block, the
+        // bound on its size keeps the required confirmations within a u8
+        if self.active_schedule.producers.len() > MAX_PRODUCERS {
+            return Err(BlockHeaderStateError::TooManyProducers.into());
+        }
+        let required_confs = (self.active_schedule.producers.len() * 2 / 3) as u8 + 1;
+        let mut confirm_count = self.confirm_count.clone();
+        if confirm_count.len() >= MAXIMUM_TRACKED_DPOS_CONFIRMATIONS {
+            confirm_count.remove(0);
+        }
+        confirm_count.push(required_confs);
+
+        let mut dpos_proposed_irreversible_blocknum = self.dpos_proposed_irreversible_blocknum;
+        // confirm the head block too
+        let mut blocks_to_confirm = u32::from(h.confirmed) + 1;
+        let mut i = confirm_count.len();
+        while i > 0 && blocks_to_confirm > 0 {
+            i -= 1;
+            confirm_count[i] = confirm_count[i].wrapping_sub(1);
+            if confirm_count[i] == 0 {
+                dpos_proposed_irreversible_blocknum = block_num - (confirm_count.len() - 1 - i) as u32;
+                confirm_count.drain(..=i);
+                break;
+            }
+            blocks_to_confirm -= 1;
+        }
+
+        let dpos_irreversible_blocknum = self.calc_dpos_last_irreversible(proauth.producer_name);
+        let producer = proauth.producer_name;
+        let valid_block_signing_authority = proauth.authority.clone();
+
+        let was_pending_promoted = !self.pending_schedule.schedule.producers.is_empty()
+            && dpos_irreversible_blocknum >= self.pending_schedule.schedule_lib_num;
+        let (active_schedule, producer_to_last_produced, producer_to_last_implied_irb) = if was_pending_promoted {
+            let active_schedule = self.pending_schedule.schedule.clone();
+            let mut last_produced = BTreeMap::new();
+            let mut last_implied_irb = BTreeMap::new();
+            for pro in active_schedule.producers.iter() {
+                let name = pro.producer_name;
+                if name == producer {
+                    last_produced.insert(name, block_num);
+                    last_implied_irb.insert(name, self.dpos_proposed_irreversible_blocknum);
+                } else {
+                    let produced = self.producer_to_last_produced.get(&name).cloned();
+                    last_produced.insert(name, produced.unwrap_or(dpos_irreversible_blocknum));
+                    let implied_irb = self.producer_to_last_implied_irb.get(&name).cloned();
+                    last_implied_irb.insert(name, implied_irb.unwrap_or(dpos_irreversible_blocknum));
+                }
+            }
+            last_produced.insert(producer, block_num);
+            (active_schedule, last_produced, last_implied_irb)
+        } else {
+            let mut last_produced = self.producer_to_last_produced.clone();
+            last_produced.insert(producer, block_num);
+            let mut last_implied_irb = self.producer_to_last_implied_irb.clone();
+            last_implied_irb.insert(producer, self.dpos_proposed_irreversible_blocknum);
+            (self.active_schedule.clone(), last_produced, last_implied_irb)
+        };
+
+        let pending_schedule = match proposed_schedule(h)? {
+            Some((schedule, schedule_hash)) => {
+                if was_pending_promoted {
+                    return Err(BlockHeaderStateError::ScheduleChangeOnPromotion.into());
+                }
+                if schedule.version != self.active_schedule.version + 1 {
+                    return Err(BlockHeaderStateError::WrongNewScheduleVersion(schedule.version).into());
+                }
+                if schedule.producers.len() > MAX_PRODUCERS {
+                    return Err(BlockHeaderStateError::TooManyProducers.into());
+                }
+                if !self.pending_schedule.schedule.producers.is_empty() {
+                    return Err(BlockHeaderStateError::PendingScheduleNotPromoted.into());
+                }
+                ScheduleInfo { schedule_lib_num: block_num, schedule_hash, schedule }
+            }
+            None if was_pending_promoted => ScheduleInfo {
+                schedule_lib_num: self.pending_schedule.schedule_lib_num,
+                schedule_hash: self.pending_schedule.schedule_hash,
+                schedule: ProducerAuthoritySchedule::new(self.pending_schedule.schedule.version, Vec::new()),
+            },
+            None => self.pending_schedule.clone(),
+        };
+
+        Ok(BlockHeaderState {
+            id: header.id()?,
+            block_num,
+            header: header.clone(),
+            dpos_proposed_irreversible_blocknum,
+            dpos_irreversible_blocknum,
+            active_schedule,
+            pending_schedule,
+            blockroot_merkle,
+            producer_to_last_produced,
+            producer_to_last_implied_irb,
+            valid_block_signing_authority,
+            additional_signatures: Vec::new(),
+            confirm_count,
+        })
+    }
+
+    /// The digest the producer signs.
+    pub fn sig_digest(&self) -> crate::Result<Checksum256> {
+        let header_bmroot = Checksum256::hash((self.header.block_header.digest()?, self.blockroot_merkle.get_root()))?;
+        Checksum256::hash((header_bmroot, self.pending_schedule.schedule_hash))
+    }
+
+    /// Check the producer signature and the additional signatures against the
+    /// block signing authority.
+    pub fn verify_signee(&self) -> crate::Result<()> {
+        self.header.verify_signatures(
+            self.blockroot_merkle.get_root(),
+            self.pending_schedule.schedule_hash,
+            &self.valid_block_signing_authority,
+            &self.additional_signatures,
+        )
+    }
+}
+
+/// The schedule proposed by a header with its hash, legacy `new_producers` keep
+/// the hash of the legacy schedule.
+pub(crate) fn proposed_schedule(h: &BlockHeader) -> crate::Result<Option<(ProducerAuthoritySchedule, Checksum256)>> {
+    let schedule = match h.new_producer_schedule()? {
+        Some(schedule) => schedule,
+        None => return Ok(None),
+    };
+    let schedule_hash = match &h.new_producers {
+        Some(legacy) => legacy.schedule_hash()?,
+        None => schedule.schedule_hash()?,
+    };
+    Ok(Some((schedule, schedule_hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProducerKey;
+    use crate::test_utils::{genesis, producer, schedule, PRODUCERS};
+
+    /// Produce the next block in the next slot.
+    fn produce(state: &BlockHeaderState, new_producers: Option<ProducerSchedule>) -> BlockHeaderState {
+        let timestamp = BlockTimestamp(state.header.block_header.timestamp.as_u32() + 1);
+        state.next(&crate::test_utils::produce(state, timestamp, new_producers)).unwrap()
+    }
+
+    #[test]
+    fn single_producer_should_advance_lib() {
+        let mut state = genesis(&PRODUCERS[..1]);
+        assert_eq!(state.block_num, 1);
+
+        let mut merkle = IncrementalMerkle::default();
+        for _ in 0..4 {
+            merkle.append(state.id).unwrap();
+            state = produce(&state, None);
+            assert_eq!(state.blockroot_merkle.get_root(), merkle.get_root());
+        }
+        assert_eq!(state.block_num, 5);
+        assert_eq!(state.dpos_proposed_irreversible_blocknum, 5);
+        assert_eq!(state.dpos_irreversible_blocknum, 4);
+        assert!(state.confirm_count.is_empty());
+    }
+
+    #[test]
+    fn schedule_should_be_promoted_once_irreversible() {
+        let mut state = produce(&genesis(&PRODUCERS[..1]), None);
+
+        let new_schedule = schedule(1, &PRODUCERS[1..]);
+        state = produce(&state, Some(new_schedule.clone()));
+        let proposed_at = state.block_num;
+        assert_eq!(state.pending_schedule.schedule_lib_num, proposed_at);
+        assert_eq!(state.pending_schedule.schedule_hash, new_schedule.schedule_hash().unwrap());
+        assert_eq!(state.active_schedule.version, 0);
+
+        // the pending schedule becomes active in the first block whose lib reaches the proposal
+        while state.active_schedule.version == 0 {
+            state = produce(&state, None);
+        }
+        assert_eq!(state.dpos_irreversible_blocknum, proposed_at);
+        assert_eq!(state.active_schedule, ProducerAuthoritySchedule::from(new_schedule));
+        assert!(state.pending_schedule.schedule.producers.is_empty());
+        assert_eq!(state.pending_schedule.schedule.version, 1);
+        assert_eq!(state.producer_to_last_implied_irb.len(), 3);
+
+        // the new producers take turns of 12 blocks and move lib forward together
+        let promoted_at = state.block_num;
+        for _ in 0..12 * 3 * 3 {
+            state = produce(&state, None);
+            assert!(state.dpos_irreversible_blocknum <= state.dpos_proposed_irreversible_blocknum);
+        }
+        assert!(state.dpos_irreversible_blocknum > promoted_at);
+    }
+
+    #[test]
+    fn invalid_headers_should_be_rejected() {
+        let state = produce(&genesis(&PRODUCERS[..1]), None);
+        let next = produce(&state, None);
+
+        let mut header = next.header.clone();
+        header.block_header.previous = Checksum256::default();
+        match state.next(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::Unlinkable)) => {}
+            _ => panic!("previous should be linked"),
+        }
+
+        let mut header = next.header.clone();
+        header.block_header.producer = producer(PRODUCERS[1]);
+        match state.next(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::WrongProducer(p))) => {
+                assert_eq!(p, producer(PRODUCERS[1]));
+            }
+            _ => panic!("producer should be the scheduled one"),
+        }
+
+        let mut header = next.header.clone();
+        header.block_header.transaction_mroot = Checksum256::hash_from_slice(b"tampered");
+        match state.next(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::UnknownSigningKey)) => {}
+            _ => panic!("a tampered header should not verify"),
+        }
+
+        let mut header = next.header.clone();
+        header.block_header.schedule_version = 1;
+        match state.next(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::WrongScheduleVersion(1))) => {}
+            _ => panic!("schedule version should be checked"),
+        }
+
+        let mut header = next.header.clone();
+        header.block_header.new_producers = Some(schedule(2, &PRODUCERS[1..]));
+        match state.next_unverified(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::WrongNewScheduleVersion(2))) => {}
+            _ => panic!("new schedule version should be checked"),
+        }
+
+        let mut header = next.header.clone();
+        let producers = (1..=MAX_PRODUCERS as u64 + 1).map(|i| ProducerKey {
+            producer_name: AccountName::from(i),
+            block_signing_key: Default::default(),
+        }).collect();
+        header.block_header.new_producers = Some(ProducerSchedule::new(1, producers));
+        match state.next_unverified(&header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::TooManyProducers)) => {}
+            _ => panic!("the size of a new schedule should be checked"),
+        }
+
+        let mut oversized = state.clone();
+        // 383 producers would need 256 confirmations
+        oversized.active_schedule.producers = vec![state.active_schedule.producers[0].clone(); 383];
+        match oversized.next_unverified(&next.header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::TooManyProducers)) => {}
+            _ => panic!("the size of the active schedule should be checked"),
+        }
+
+        match next.next(&next.header) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::TimestampNotIncreasing)) => {}
+            _ => panic!("a header can't be applied twice"),
+        }
+    }
+}
@@ -0,0 +1,478 @@
+//! Read and write the `blocks.log` and `blocks.index` files of nodeos.
+//! <https://github.com/EOSIO/eos/blob/v2.0.9/libraries/chain/block_log.cpp>
+//!
+//! `blocks.log` starts with the version, the first block number from version 2, then
+//! the genesis state when the log starts at block 1 or the chain id, and from version 2
+//! a totem. Every packed `SignedBlock` follows, trailed by the position it starts at so
+//! the log can be walked backward. `blocks.index` holds the position of every block.
+use std::fs::File;
+use std::io::{Read as IoRead, Seek, SeekFrom, Write as IoWrite};
+use std::path::Path;
+use crate::{BlockchainParameters, Checksum256, NumBytes, PublicKey, Read, SerializeData, SignedBlock, TimePoint, Write};
+
+/// The version written, the one of nodeos 2.0.
+pub const BLOCK_LOG_VERSION: u32 = 3;
+/// Written after the header of logs from version 2.
+pub const BLOCK_LOG_TOTEM: u64 = u64::MAX;
+/// The header is read in one go, a genesis state is far smaller.
+const MAX_HEADER_SIZE: u64 = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockLogError {
+    /// The version is not one of nodeos 1.x or 2.0
+    UnsupportedVersion(u32),
+    /// The header is not followed by the totem
+    MissingTotem,
+    /// Logs starting at block 1 and logs before version 3 carry the genesis state,
+    /// the others the chain id
+    InvalidChainContext,
+    /// The block is not in the log
+    BlockNotFound(u32),
+    /// The block doesn't follow the last block of the log
+    NotNextBlock(u32),
+    /// The block at this position doesn't match its trailing position or the index
+    PositionMismatch(u64),
+}
+
+impl From<BlockLogError> for crate::Error {
+    fn from(e: BlockLogError) -> crate::Error {
+        crate::Error::BlockLogError(e)
+    }
+}
+
+/// Field order matters, the chain id is the digest of the packed genesis state.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct GenesisState {
+    pub initial_timestamp: TimePoint,
+    pub initial_key: PublicKey,
+    pub initial_configuration: BlockchainParameters,
+}
+
+impl GenesisState {
+    /// The chain id is the digest of the genesis state.
+    pub fn chain_id(&self) -> crate::Result<Checksum256> {
+        Checksum256::hash(self.clone())
+    }
+}
+
+/// What identifies the chain in the header of a log.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainContext {
+    Genesis(GenesisState),
+    ChainId(Checksum256),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockLogHeader {
+    pub version: u32,
+    pub first_block_num: u32,
+    pub chain_context: ChainContext,
+}
+
+impl BlockLogHeader {
+    /// The header nodeos 2.0 writes, a log starting at block 1 needs the genesis state.
+    pub fn new(first_block_num: u32, chain_context: ChainContext) -> crate::Result<Self> {
+        let header = BlockLogHeader { version: BLOCK_LOG_VERSION, first_block_num, chain_context };
+        header.check()?;
+        Ok(header)
+    }
+
+    pub fn chain_id(&self) -> crate::Result<Checksum256> {
+        match &self.chain_context {
+            ChainContext::Genesis(genesis) => genesis.chain_id(),
+            ChainContext::ChainId(chain_id) => Ok(*chain_id),
+        }
+    }
+
+    fn has_genesis(version: u32, first_block_num: u32) -> bool {
+        version < 3 || first_block_num == 1
+    }
+
+    fn check(&self) -> Result<(), BlockLogError> {
+        if self.version == 0 || self.version > BLOCK_LOG_VERSION {
+            return Err(BlockLogError::UnsupportedVersion(self.version));
+        }
+        let valid = match self.chain_context {
+            ChainContext::Genesis(_) => Self::has_genesis(self.version, self.first_block_num),
+            ChainContext::ChainId(_) => !Self::has_genesis(self.version, self.first_block_num),
+        };
+        if !valid || (self.version == 1 && self.first_block_num != 1) {
+            return Err(BlockLogError::InvalidChainContext);
+        }
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        let mut data = self.version.to_le_bytes().to_vec();
+        if self.version > 1 {
+            data.extend_from_slice(&self.first_block_num.to_le_bytes());
+        }
+        match &self.chain_context {
+            ChainContext::Genesis(genesis) => data.extend(genesis.to_serialize_data()?),
+            ChainContext::ChainId(chain_id) => data.extend_from_slice(chain_id.as_bytes()),
+        }
+        if self.version > 1 {
+            data.extend_from_slice(&BLOCK_LOG_TOTEM.to_le_bytes());
+        }
+        Ok(data)
+    }
+
+    /// Read the header at the start of `log`, with the position of the first block.
+    fn read_from(log: &mut File) -> crate::Result<(Self, u64)> {
+        let mut data = Vec::new();
+        log.seek(SeekFrom::Start(0))?;
+        (&*log).take(MAX_HEADER_SIZE).read_to_end(&mut data)?;
+
+        let pos = &mut 0;
+        let version = u32::read(&data, pos).map_err(crate::Error::BytesReadError)?;
+        if version == 0 || version > BLOCK_LOG_VERSION {
+            return Err(BlockLogError::UnsupportedVersion(version).into());
+        }
+        let first_block_num = if version > 1 {
+            u32::read(&data, pos).map_err(crate::Error::BytesReadError)?
+        } else {
+            1
+        };
+        let chain_context = if Self::has_genesis(version, first_block_num) {
+            ChainContext::Genesis(GenesisState::read(&data, pos).map_err(crate::Error::BytesReadError)?)
+        } else {
+            ChainContext::ChainId(Checksum256::read(&data, pos).map_err(crate::Error::BytesReadError)?)
+        };
+        if version > 1 && u64::read(&data, pos).map_err(crate::Error::BytesReadError)? != BLOCK_LOG_TOTEM {
+            return Err(BlockLogError::MissingTotem.into());
+        }
+
+        Ok((BlockLogHeader { version, first_block_num, chain_context }, *pos as u64))
+    }
+}
+
+/// Random access to the blocks of a log through its index.
+#[derive(Debug)]
+pub struct BlockLogReader {
+    log: File,
+    log_len: u64,
+    header: BlockLogHeader,
+    /// Position of every block, starting with block `first_block_num`
+    positions: Vec<u64>,
+}
+
+impl BlockLogReader {
+    /// Open a log and its index, use `construct_index` first when the index is missing.
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(log_path: P, index_path: Q) -> crate::Result<Self> {
+        let mut log = File::open(log_path)?;
+        let (header, _) = BlockLogHeader::read_from(&mut log)?;
+        let log_len = log.metadata()?.len();
+
+        let mut data = Vec::new();
+        File::open(index_path)?.read_to_end(&mut data)?;
+        let positions = data.chunks(8)
+            .map(|position| u64::read(position, &mut 0))
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(crate::Error::BytesReadError)?;
+
+        Ok(BlockLogReader { log, log_len, header, positions })
+    }
+
+    pub fn header(&self) -> &BlockLogHeader {
+        &self.header
+    }
+
+    pub fn first_block_num(&self) -> u32 {
+        self.header.first_block_num
+    }
+
+    /// `None` for a log without blocks.
+    pub fn last_block_num(&self) -> Option<u32> {
+        if self.positions.is_empty() {
+            return None;
+        }
+        Some(self.header.first_block_num + self.positions.len() as u32 - 1)
+    }
+
+    /// Number of blocks in the log.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn read_block(&mut self, block_num: u32) -> crate::Result<SignedBlock> {
+        let index = block_num.checked_sub(self.header.first_block_num)
+            .map(|index| index as usize)
+            .filter(|index| *index < self.positions.len())
+            .ok_or(BlockLogError::BlockNotFound(block_num))?;
+        let start = self.positions[index];
+        let end = self.positions.get(index + 1).cloned().unwrap_or(self.log_len);
+        if end < start + 8 {
+            return Err(BlockLogError::PositionMismatch(start).into());
+        }
+
+        let mut data = vec![0u8; (end - start) as usize];
+        self.log.seek(SeekFrom::Start(start))?;
+        self.log.read_exact(&mut data)?;
+        let (block_data, trailer) = data.split_at(data.len() - 8);
+        let pos = &mut 0;
+        let block = SignedBlock::read(block_data, pos).map_err(crate::Error::BytesReadError)?;
+        let trailing_position = u64::read(trailer, &mut 0).map_err(crate::Error::BytesReadError)?;
+        if *pos != block_data.len() || trailing_position != start || block.block_num() != block_num {
+            return Err(BlockLogError::PositionMismatch(start).into());
+        }
+        Ok(block)
+    }
+
+    /// Iterate over the blocks in order.
+    pub fn blocks(&mut self) -> BlockLogIter<'_> {
+        let block_num = self.header.first_block_num;
+        BlockLogIter { reader: self, block_num }
+    }
+}
+
+pub struct BlockLogIter<'a> {
+    reader: &'a mut BlockLogReader,
+    block_num: u32,
+}
+
+impl<'a> Iterator for BlockLogIter<'a> {
+    type Item = crate::Result<SignedBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.last_block_num()? < self.block_num {
+            return None;
+        }
+        let block = self.reader.read_block(self.block_num);
+        self.block_num += 1;
+        Some(block)
+    }
+}
+
+/// Rebuild `blocks.index` from `blocks.log`, walking back from the last block like nodeos.
+pub fn construct_index<P: AsRef<Path>, Q: AsRef<Path>>(log_path: P, index_path: Q) -> crate::Result<()> {
+    let mut log = File::open(log_path)?;
+    let (_, first_position) = BlockLogHeader::read_from(&mut log)?;
+    let mut end = log.metadata()?.len();
+    let mut positions = Vec::new();
+    let mut trailer = [0u8; 8];
+    while end > first_position {
+        if end < first_position + 8 {
+            return Err(BlockLogError::PositionMismatch(end).into());
+        }
+        log.seek(SeekFrom::Start(end - 8))?;
+        log.read_exact(&mut trailer)?;
+        let position = u64::from_le_bytes(trailer);
+        if position < first_position || position >= end - 8 {
+            return Err(BlockLogError::PositionMismatch(position).into());
+        }
+        positions.push(position);
+        end = position;
+    }
+
+    let data: Vec<u8> = positions.iter().rev().flat_map(|position| position.to_le_bytes().to_vec()).collect();
+    File::create(index_path)?.write_all(&data)?;
+    Ok(())
+}
+
+/// Write a new log and its index block after block.
+#[derive(Debug)]
+pub struct BlockLogWriter {
+    log: File,
+    index: File,
+    header: BlockLogHeader,
+    next_block_num: u32,
+    position: u64,
+}
+
+impl BlockLogWriter {
+    /// Create the log and its index, existing files are overwritten.
+    pub fn create<P: AsRef<Path>, Q: AsRef<Path>>(
+        log_path: P,
+        index_path: Q,
+        header: BlockLogHeader,
+    ) -> crate::Result<Self> {
+        header.check()?;
+        let data = header.to_bytes()?;
+        let mut log = File::create(log_path)?;
+        log.write_all(&data)?;
+        let index = File::create(index_path)?;
+
+        Ok(BlockLogWriter {
+            log,
+            index,
+            next_block_num: header.first_block_num,
+            position: data.len() as u64,
+            header,
+        })
+    }
+
+    pub fn header(&self) -> &BlockLogHeader {
+        &self.header
+    }
+
+    /// Append the block following the last one, returns its position in the log.
+    pub fn append(&mut self, block: &SignedBlock) -> crate::Result<u64> {
+        if block.block_num() != self.next_block_num {
+            return Err(BlockLogError::NotNextBlock(block.block_num()).into());
+        }
+        let position = self.position;
+        let mut data = block.to_serialize_data()?;
+        data.extend_from_slice(&position.to_le_bytes());
+        self.log.write_all(&data)?;
+        self.index.write_all(&position.to_le_bytes())?;
+
+        self.position += data.len() as u64;
+        self.next_block_num += 1;
+        Ok(position)
+    }
+
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.log.flush()?;
+        self.index.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::{utils::bitutil, BlockTimestamp, Extension, SignedBlockHeader};
+
+    fn paths(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir();
+        let prefix = format!("{}-{}", name, std::process::id());
+        (dir.join(format!("{}.log", prefix)), dir.join(format!("{}.index", prefix)))
+    }
+
+    fn blocks(first_block_num: u32, count: u32) -> Vec<SignedBlock> {
+        let mut previous = Checksum256::default();
+        if first_block_num > 1 {
+            previous = Checksum256::hash_from_slice(b"previous block");
+            let num = bitutil::endian_reverse_u32(first_block_num - 1) as u64;
+            previous.set_hash0((previous.hash0() & 0xffffffff00000000) | num);
+        }
+        (first_block_num..first_block_num + count).map(|block_num| {
+            let mut header = SignedBlockHeader::default();
+            header.block_header.timestamp = BlockTimestamp(block_num);
+            header.block_header.previous = previous;
+            let mut block = SignedBlock::new(header);
+            // blocks of different sizes
+            block.block_extensions = vec![Extension(9, vec![0; block_num as usize % 3])];
+            previous = block.id().unwrap();
+            block
+        }).collect()
+    }
+
+    fn write(log_path: &PathBuf, index_path: &PathBuf, header: BlockLogHeader, blocks: &[SignedBlock]) -> Vec<u64> {
+        let mut writer = BlockLogWriter::create(log_path, index_path, header).unwrap();
+        let positions = blocks.iter().map(|block| writer.append(block).unwrap()).collect();
+        writer.flush().unwrap();
+        positions
+    }
+
+    #[test]
+    fn mainnet_genesis_chain_id_should_work() {
+        let genesis = GenesisState {
+            // 2018-06-08T08:08:08.888
+            initial_timestamp: TimePoint::from(1_528_445_288_888_000),
+            initial_key: "EOS7EarnUhcyYqmdnPon8rm7mBCTnBoot6o7fE2WzjvEX2TdggbL3".parse().unwrap(),
+            initial_configuration: BlockchainParameters {
+                max_block_net_usage: 1_048_576,
+                target_block_net_usage_pct: 1_000,
+                max_transaction_net_usage: 524_288,
+                base_per_transaction_net_usage: 12,
+                net_usage_leeway: 500,
+                context_free_discount_net_usage_num: 20,
+                context_free_discount_net_usage_den: 100,
+                max_block_cpu_usage: 200_000,
+                target_block_cpu_usage_pct: 1_000,
+                max_transaction_cpu_usage: 150_000,
+                min_transaction_cpu_usage: 100,
+                max_transaction_lifetime: 3_600,
+                deferred_trx_expiration_window: 600,
+                max_transaction_delay: 3_888_000,
+                max_inline_action_size: 4_096,
+                max_inline_action_depth: 4,
+                max_authority_depth: 6,
+            },
+        };
+        assert_eq!(
+            genesis.chain_id().unwrap(),
+            "aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906".into()
+        );
+    }
+
+    #[test]
+    fn block_log_with_genesis_should_work() {
+        let (log_path, index_path) = paths("block_log_with_genesis");
+        let genesis = GenesisState::default();
+        let header = BlockLogHeader::new(1, ChainContext::Genesis(genesis.clone())).unwrap();
+        let blocks = blocks(1, 5);
+        assert_eq!(blocks[0].block_num(), 1);
+        let positions = write(&log_path, &index_path, header.clone(), &blocks);
+        // version, first block number, a 110 bytes genesis state and the totem
+        assert_eq!(positions[0], 4 + 4 + 110 + 8);
+
+        let data = std::fs::read(&log_path).unwrap();
+        assert_eq!(data[..8], [3, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(data[118..126], [0xff; 8]);
+        assert_eq!(data[positions[1] as usize - 8..positions[1] as usize], positions[0].to_le_bytes());
+
+        let mut reader = BlockLogReader::open(&log_path, &index_path).unwrap();
+        assert_eq!(reader.header(), &header);
+        assert_eq!(reader.header().chain_id().unwrap(), genesis.chain_id().unwrap());
+        assert_eq!((reader.first_block_num(), reader.last_block_num(), reader.len()), (1, Some(5), 5));
+        assert_eq!(reader.read_block(3).unwrap(), blocks[2]);
+        assert_eq!(reader.read_block(5).unwrap(), blocks[4]);
+        for block_num in [0, 6].iter() {
+            match reader.read_block(*block_num) {
+                Err(crate::Error::BlockLogError(BlockLogError::BlockNotFound(n))) => assert_eq!(n, *block_num),
+                r => panic!("expect a missing block, got {:?}", r),
+            }
+        }
+        let read: Vec<SignedBlock> = reader.blocks().map(Result::unwrap).collect();
+        assert_eq!(read, blocks);
+
+        std::fs::remove_file(log_path).unwrap();
+        std::fs::remove_file(index_path).unwrap();
+    }
+
+    #[test]
+    fn block_log_with_chain_id_should_work() {
+        let (log_path, index_path) = paths("block_log_with_chain_id");
+        let chain_id = GenesisState::default().chain_id().unwrap();
+        match BlockLogHeader::new(10, ChainContext::Genesis(GenesisState::default())) {
+            Err(crate::Error::BlockLogError(BlockLogError::InvalidChainContext)) => {}
+            r => panic!("a log starting at block 10 carries the chain id, got {:?}", r),
+        }
+        let header = BlockLogHeader::new(10, ChainContext::ChainId(chain_id)).unwrap();
+        let blocks = blocks(10, 4);
+
+        let mut writer = BlockLogWriter::create(&log_path, &index_path, header.clone()).unwrap();
+        assert_eq!(writer.append(&blocks[0]).unwrap(), 4 + 4 + 32 + 8);
+        match writer.append(&blocks[2]) {
+            Err(crate::Error::BlockLogError(BlockLogError::NotNextBlock(12))) => {}
+            r => panic!("block 11 is next, got {:?}", r),
+        }
+        for block in blocks[1..].iter() {
+            writer.append(block).unwrap();
+        }
+        writer.flush().unwrap();
+
+        // the index nodeos would rebuild is the one written
+        let index = std::fs::read(&index_path).unwrap();
+        std::fs::remove_file(&index_path).unwrap();
+        construct_index(&log_path, &index_path).unwrap();
+        assert_eq!(std::fs::read(&index_path).unwrap(), index);
+
+        let mut reader = BlockLogReader::open(&log_path, &index_path).unwrap();
+        assert_eq!(reader.header().chain_id().unwrap(), chain_id);
+        assert_eq!(reader.last_block_num(), Some(13));
+        assert_eq!(reader.read_block(12).unwrap(), blocks[2]);
+        assert_eq!(reader.blocks().count(), 4);
+
+        std::fs::remove_file(log_path).unwrap();
+        std::fs::remove_file(index_path).unwrap();
+    }
+}
@@ -1,11 +1,15 @@
 //! <https://github.com/EOSIO/eosio.cdt/blob/4985359a30da1f883418b7133593f835927b8046/libraries/eosiolib/core/eosio/time.hpp#L134-L210>
 use crate::{TimePoint, TimePointSec, NumBytes, Read, Write};
 use alloc::string::ToString;
+use core::convert::TryFrom;
 use chrono::{Utc, TimeZone, SecondsFormat};
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::ser::{Serialize, Serializer};
 
+/// Consecutive blocks each producer produces in its turn.
+pub const PRODUCER_REPETITIONS: u32 = 12;
+
 /// This class is used in the block headers to represent the block time
 /// It is a parameterised class that takes an Epoch in milliseconds and
 /// and an interval in milliseconds and computes the number of slots.
@@ -30,7 +34,7 @@ impl BlockTimestamp {
     /// Epoch is 2000-01-01T00:00.000Z.
     pub const BLOCK_TIMESTAMP_EPOCH: u64 = 946_684_800_000;
 
-    /// Gets the milliseconds
+    /// Gets the slots since the epoch, same as `slot`
     #[inline]
     pub const fn as_u32(self) -> u32 {
         self.0
@@ -40,6 +44,77 @@ impl BlockTimestamp {
     pub fn now() -> Self {
         TimePointSec::now().into()
     }
+
+    /// Slots since the epoch, there is one slot per block interval.
+    #[inline]
+    pub const fn slot(self) -> u32 {
+        self.0
+    }
+
+    /// The next slot, `None` past the last one.
+    #[inline]
+    pub const fn next(self) -> Option<Self> {
+        match self.0.checked_add(1) {
+            Some(slot) => Some(Self(slot)),
+            None => None,
+        }
+    }
+
+    /// The previous slot, `None` before the epoch.
+    #[inline]
+    pub const fn prev(self) -> Option<Self> {
+        match self.0.checked_sub(1) {
+            Some(slot) => Some(Self(slot)),
+            None => None,
+        }
+    }
+
+    /// Position of the slot in the round of `PRODUCER_REPETITIONS` blocks it belongs to.
+    #[inline]
+    pub const fn slot_in_round(self) -> u32 {
+        self.0 % PRODUCER_REPETITIONS
+    }
+
+    /// First slot of the round the slot belongs to.
+    #[inline]
+    pub const fn round_start(self) -> Self {
+        Self(self.0 - self.slot_in_round())
+    }
+
+    /// First slot of the round after the one the slot belongs to, `None` if it is past the last slot.
+    #[inline]
+    pub const fn next_round_start(self) -> Option<Self> {
+        match self.round_start().0.checked_add(PRODUCER_REPETITIONS) {
+            Some(slot) => Some(Self(slot)),
+            None => None,
+        }
+    }
+
+    /// Index of the producer scheduled for the slot among `producer_count` producers,
+    /// each producing a round in schedule order.
+    pub fn producer_index(self, producer_count: usize) -> Option<usize> {
+        if producer_count == 0 {
+            return None;
+        }
+        let index = self.0 as usize % (producer_count * PRODUCER_REPETITIONS as usize);
+        Some(index / PRODUCER_REPETITIONS as usize)
+    }
+
+    /// First slot, at or after this one, starting a round of the producer at `index`
+    /// among `producer_count` producers, `None` if it is past the last slot.
+    pub fn next_round_start_of(self, index: usize, producer_count: usize) -> Option<Self> {
+        if index >= producer_count {
+            return None;
+        }
+        let cycle = u32::try_from(producer_count).ok()?.checked_mul(PRODUCER_REPETITIONS)?;
+        // index < producer_count so the offset is below the cycle
+        let offset = index as u32 * PRODUCER_REPETITIONS;
+        let mut start = (self.0 - self.0 % cycle).checked_add(offset)?;
+        if start < self.0 {
+            start = start.checked_add(cycle)?;
+        }
+        Some(Self(start))
+    }
 }
 
 #[cfg(feature = "std")]
@@ -107,11 +182,19 @@ impl From<TimePoint> for BlockTimestamp {
     #[inline]
     fn from(t: TimePoint) -> Self {
         let micro_since_epoch = t.time_since_epoch();
-        let msec_since_epoch  = micro_since_epoch / 1_000_000;
+        let msec_since_epoch  = micro_since_epoch / 1_000;
         Self(((msec_since_epoch - BlockTimestamp::BLOCK_TIMESTAMP_EPOCH as i64) / BlockTimestamp::BLOCK_INTERVAL_MS as i64) as u32)
     }
 }
 
+impl From<BlockTimestamp> for TimePoint {
+    #[inline]
+    fn from(t: BlockTimestamp) -> Self {
+        let msec_since_epoch = t.0 as i64 * BlockTimestamp::BLOCK_INTERVAL_MS as i64 + BlockTimestamp::BLOCK_TIMESTAMP_EPOCH as i64;
+        TimePoint::from(msec_since_epoch * 1_000)
+    }
+}
+
 impl From<TimePointSec> for BlockTimestamp {
     #[inline]
     fn from(t: TimePointSec) -> Self {
@@ -141,4 +224,50 @@ mod tests {
         assert_eq!(block_time, block_timestamp);
         assert_eq!(BlockTimestamp::from(100), BlockTimestamp(100));
     }
+
+    #[test]
+    fn slot_arithmetic_should_work() {
+        let t = BlockTimestamp(1_000);
+        assert_eq!(t.slot(), 1_000);
+        assert_eq!(t.next(), Some(BlockTimestamp(1_001)));
+        assert_eq!(t.prev(), Some(BlockTimestamp(999)));
+        assert_eq!(t.slot_in_round(), 4);
+        assert_eq!(t.round_start(), BlockTimestamp(996));
+        assert_eq!(t.next_round_start(), Some(BlockTimestamp(1_008)));
+        assert_eq!(BlockTimestamp(996).round_start(), BlockTimestamp(996));
+
+        let last = BlockTimestamp(u32::MAX);
+        assert_eq!(last.next(), None);
+        assert_eq!(BlockTimestamp(0).prev(), None);
+        assert_eq!(last.next_round_start(), None);
+
+        let tp = TimePoint::from(t);
+        // 946_684_800 s to the epoch then 1_000 slots of 500 ms, in microseconds
+        assert_eq!(tp.as_i64(), 946_685_300_000_000);
+        assert_eq!(tp.to_string(), "2000-01-01T00:08:20.000Z");
+        assert_eq!(BlockTimestamp::from(tp), t);
+        assert_eq!(t.to_string(), tp.to_string());
+    }
+
+    #[test]
+    fn producer_slots_should_work() {
+        // 1000 % 36 = 28, the third producer's round
+        let t = BlockTimestamp(1_000);
+        assert_eq!(t.producer_index(3), Some(2));
+        assert_eq!(BlockTimestamp(1_008).producer_index(3), Some(0));
+        assert_eq!(t.producer_index(1), Some(0));
+        assert_eq!(t.producer_index(0), None);
+
+        assert_eq!(t.next_round_start_of(0, 3), Some(BlockTimestamp(1_008)));
+        assert_eq!(t.next_round_start_of(1, 3), Some(BlockTimestamp(1_020)));
+        assert_eq!(t.next_round_start_of(2, 3), Some(BlockTimestamp(1_032)));
+        assert_eq!(BlockTimestamp(996).next_round_start_of(2, 3), Some(BlockTimestamp(996)));
+        assert_eq!(t.next_round_start_of(3, 3), None);
+
+        // u32::MAX - 3 starts the last full cycle, later rounds are past the last slot
+        let t = BlockTimestamp(u32::MAX - 3);
+        assert_eq!(t.next_round_start_of(0, 3), Some(t));
+        assert_eq!(t.next_round_start_of(2, 3), None);
+        assert_eq!(BlockTimestamp(u32::MAX).next_round_start_of(0, 3), None);
+    }
 }
@@ -1,19 +1,11 @@
 //! <https://github.com/EOSIO/eosio.cdt/blob/4985359a30da1f883418b7133593f835927b8046/libraries/eosiolib/contracts/eosio/privileged.hpp#L40-L160>
 use crate::{NumBytes, Read, Write};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 
 /// Tunable blockchain configuration that can be changed via consensus
-#[derive(
-    Read,
-    Write,
-    NumBytes,
-    Clone,
-    Default,
-    Debug,
-    PartialEq,
-    PartialOrd,
-    serde::Serialize,
-    serde::Deserialize,
-)]
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
 #[eosio_core_root_path = "crate"]
 pub struct BlockchainParameters {
     /// The maximum net usage in instructions for a block
@@ -1,4 +1,5 @@
 use crate::UnsignedInt;
+use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -111,7 +112,8 @@ impl_num!(
     i32, 4
     u64, 8
     i64, 8
-); // TODO i8 u128 i128
+    u128, 16
+); // TODO i8 i128
 
 impl NumBytes for f32 {
     #[inline]
@@ -418,6 +420,40 @@ where
     }
 }
 
+impl<T> NumBytes for Box<T>
+where
+    T: NumBytes,
+{
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        (**self).num_bytes()
+    }
+}
+
+impl<T> Read for Box<T>
+where
+    T: Read,
+{
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        T::read(bytes, pos).map(Box::new)
+    }
+}
+
+impl<T> Write for Box<T>
+where
+    T: Write,
+{
+    #[inline]
+    fn write(
+        &self,
+        bytes: &mut [u8],
+        pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        (**self).write(bytes, pos)
+    }
+}
+
 impl NumBytes for String {
     #[inline]
     fn num_bytes(&self) -> usize {
@@ -702,6 +738,7 @@ mod tests {
         test_u16, u16, 1_u16
         test_u32, u32, 1_u32
         test_u64, u64, 1_u64
+        test_u128, u128, 1_u128
         // test_i8, i8, 1i8
         test_i16, i16, -1_i16
         test_i32, i32, -1_i32
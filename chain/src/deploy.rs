@@ -0,0 +1,127 @@
+//! Build the `setcode` and `setabi` actions that deploy a contract.
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::{AbiDef, AccountName, Action, Checksum256, PermissionLevel, SetAbi, SetCode, ToAction, SYSTEM_CONTRACT};
+
+/// The wasm code and packed ABI of a contract to deploy to `account`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deployment {
+    pub account: AccountName,
+    pub wasm: Vec<u8>,
+    /// The ABI packed as `setabi` expects
+    pub abi: Vec<u8>,
+}
+
+impl Deployment {
+    pub fn new(account: AccountName, wasm: Vec<u8>, abi: &AbiDef) -> crate::Result<Self> {
+        Ok(Deployment { account, wasm, abi: abi.to_bytes()? })
+    }
+
+    /// Same as `new` with the ABI in the JSON form produced by `eosio-cpp`.
+    #[cfg(feature = "std")]
+    pub fn from_json_abi(account: AccountName, wasm: Vec<u8>, abi: &str) -> crate::Result<Self> {
+        Self::new(account, wasm, &AbiDef::from_json(abi)?)
+    }
+
+    /// The hash nodeos reports as `code_hash` once the code is deployed.
+    pub fn code_hash(&self) -> Checksum256 {
+        Checksum256::hash_from_slice(&self.wasm)
+    }
+
+    /// The hash nodeos reports as `abi_hash` once the ABI is deployed.
+    pub fn abi_hash(&self) -> Checksum256 {
+        Checksum256::hash_from_slice(&self.abi)
+    }
+
+    pub fn set_code(&self) -> SetCode {
+        SetCode { account: self.account, vmtype: 0, vmversion: 0, code: self.wasm.clone() }
+    }
+
+    pub fn set_abi(&self) -> SetAbi {
+        SetAbi { account: self.account, abi: self.abi.clone() }
+    }
+
+    /// The `setcode` and `setabi` actions authorized by the account's `active`
+    /// permission, an action is left out when its hash matches the deployed one.
+    pub fn actions(
+        &self,
+        deployed_code_hash: Option<Checksum256>,
+        deployed_abi_hash: Option<Checksum256>,
+    ) -> crate::Result<Vec<Action>> {
+        let authorization = vec![PermissionLevel::active(self.account)];
+        self.actions_with_authorization(authorization, deployed_code_hash, deployed_abi_hash)
+    }
+
+    pub fn actions_with_authorization(
+        &self,
+        authorization: Vec<PermissionLevel>,
+        deployed_code_hash: Option<Checksum256>,
+        deployed_abi_hash: Option<Checksum256>,
+    ) -> crate::Result<Vec<Action>> {
+        let mut actions = Vec::new();
+        if deployed_code_hash != Some(self.code_hash()) {
+            actions.push(self.set_code().to_action(SYSTEM_CONTRACT.into(), authorization.clone())?);
+        }
+        if deployed_abi_hash != Some(self.abi_hash()) {
+            actions.push(self.set_abi().to_action(SYSTEM_CONTRACT.into(), authorization)?);
+        }
+
+        Ok(actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    const HELLO_ABI: &str = r#"{
+        "version": "eosio::abi/1.1",
+        "structs": [{"name": "hi", "base": "", "fields": [{"name": "user", "type": "name"}]}],
+        "actions": [{"name": "hi", "type": "hi", "ricardian_contract": ""}]
+    }"#;
+
+    fn deployment() -> Deployment {
+        let wasm = hex::decode("0061736d01000000").unwrap();
+        Deployment::from_json_abi(AccountName::from_str("alice").unwrap(), wasm, HELLO_ABI).unwrap()
+    }
+
+    #[test]
+    fn deployment_actions_should_work() {
+        let deployment = deployment();
+        assert_eq!(
+            deployment.code_hash().to_string(),
+            "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert_eq!(
+            deployment.abi_hash().to_string(),
+            "00fee7c16a8accc30c360357566f95fb705e6bb53c5fd1b1d940e2bca789e839"
+        );
+
+        let actions = deployment.actions(None, None).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].account.to_string(), "eosio");
+        assert_eq!(actions[0].name.to_string(), "setcode");
+        assert_eq!(actions[0].authorization, vec![PermissionLevel::from_str("alice", "active").unwrap()]);
+        assert_eq!(hex::encode(&actions[0].data), "0000000000855c340000080061736d01000000");
+        assert_eq!(actions[1].name.to_string(), "setabi");
+        assert_eq!(
+            hex::encode(&actions[1].data),
+            "0000000000855c34330e656f73696f3a3a6162692f312e31000102686900010475736572046e616d6501000000000000806b02686900000000000000"
+        );
+    }
+
+    #[test]
+    fn deployment_should_skip_deployed_parts() {
+        let deployment = deployment();
+        let actions = deployment.actions(Some(deployment.code_hash()), None).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].name.to_string(), "setabi");
+
+        let actions = deployment.actions(Some(Checksum256::default()), Some(deployment.abi_hash())).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].name.to_string(), "setcode");
+
+        assert!(deployment.actions(Some(deployment.code_hash()), Some(deployment.abi_hash())).unwrap().is_empty());
+    }
+}
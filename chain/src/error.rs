@@ -1,15 +1,24 @@
 use crate::{
-    ParseAssetError, ParseNameError,
-    ParseSymbolError, ReadError, WriteError
+    ActionProofError, AuthorityError, BlockHeaderStateError, ExtensionError, ForkDatabaseError, ParseAssetError,
+    ParseNameError, PermissionLinkError, ParseSymbolError, ReadError, WriteError
 };
+#[cfg(feature = "std")]
+use crate::{BlockLogError, SnapshotError};
 use keys::error as KeyError;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Clone, Debug)]
 pub enum Error {
+    ActionProofError(ActionProofError),
+    BatchVerificationError(usize),
+    BlockHeaderStateError(BlockHeaderStateError),
+    #[cfg(feature = "std")]
+    BlockLogError(BlockLogError),
     BytesReadError(ReadError),
     BytesWriteError(WriteError),
+    ExtensionError(ExtensionError),
+    ForkDatabaseError(ForkDatabaseError),
     FromHexError(hex::FromHexError),
     Keys(KeyError::Error),
     ParseAssetErr(ParseAssetError),
@@ -17,7 +26,22 @@ pub enum Error {
     ParseSymbolError(ParseSymbolError),
     FromTrxKindsError,
     IncreMerkleError,
+    InvalidAbiJson(alloc::string::String),
+    InvalidAuthority(AuthorityError),
     InvalidLength,
+    InvalidTransactionMroot,
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
     NoNewProducersList,
+    PermissionLinkError(PermissionLinkError),
+    #[cfg(feature = "std")]
+    SnapshotError(SnapshotError),
     VerificationError(KeyError::Error),
 }
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::Io(e.kind())
+    }
+}
@@ -0,0 +1,123 @@
+//! Actions of the EOS EVM contract
+//! <https://github.com/eosnetworkfoundation/eos-evm-contract/blob/main/include/evm_runtime/evm_contract.hpp>
+use alloc::vec;
+use alloc::vec::Vec;
+use keys::evm::{Address, SignedTransaction};
+use crate::{AccountName, Action, NumBytes, PermissionLevel, Read, SerializeData, ToAction, Write};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The account the EOS EVM contract is deployed to, `eosio.evm`.
+pub const EVM_CONTRACT: u64 = 6_138_663_583_658_016_768;
+
+/// Push a signed RLP encoded Ethereum transaction, `eosio.evm::pushtx`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct EvmPushTx {
+    /// The account that pays for and collects the fee of the transaction
+    pub miner: AccountName,
+    pub rlptx: Vec<u8>,
+}
+
+impl EvmPushTx {
+    pub fn new(miner: AccountName, trx: &SignedTransaction) -> Self {
+        EvmPushTx { miner, rlptx: trx.rlp_bytes() }
+    }
+
+    /// Build the action against `eosio.evm`, authorized by the miner's `active` permission.
+    pub fn to_evm_action(&self) -> crate::Result<Action> {
+        self.to_action(EVM_CONTRACT.into(), vec![PermissionLevel::active(self.miner)])
+    }
+}
+
+impl ToAction for EvmPushTx {
+    /// `pushtx`
+    const NAME: u64 = 12_587_803_899_030_667_264;
+}
+
+/// Call an EVM contract from a native account, `eosio.evm::call`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct EvmCall {
+    pub from: AccountName,
+    /// The 20 bytes target address
+    pub to: Vec<u8>,
+    /// Amount in wei as a 32 bytes big endian integer
+    pub value: Vec<u8>,
+    pub data: Vec<u8>,
+    pub gas_limit: u64,
+}
+
+impl EvmCall {
+    pub fn new(from: AccountName, to: Address, value: u128, data: Vec<u8>, gas_limit: u64) -> Self {
+        let mut value_be = vec![0u8; 32];
+        value_be[16..].copy_from_slice(&value.to_be_bytes());
+
+        EvmCall { from, to: to.as_bytes().to_vec(), value: value_be, data, gas_limit }
+    }
+
+    /// Build the action against `eosio.evm`, authorized by the caller's `active` permission.
+    pub fn to_evm_action(&self) -> crate::Result<Action> {
+        self.to_action(EVM_CONTRACT.into(), vec![PermissionLevel::active(self.from)])
+    }
+}
+
+impl ToAction for EvmCall {
+    /// `call`
+    const NAME: u64 = 4_729_641_625_855_197_184;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use keys::evm::Transaction;
+    use keys::secret::SecretKey;
+
+    fn signed_transaction() -> SignedTransaction {
+        let sk = SecretKey::from_hex("4646464646464646464646464646464646464646464646464646464646464646").unwrap();
+        let trx = Transaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21000,
+            to: Some(Address::from_str("0x3535353535353535353535353535353535353535").unwrap()),
+            value: 1_000_000_000_000_000_000,
+            data: vec![],
+        };
+        trx.sign(&sk, Some(1)).unwrap()
+    }
+
+    #[test]
+    fn evm_pushtx_should_work() {
+        let pushtx = EvmPushTx::new(AccountName::from_str("eosio").unwrap(), &signed_transaction());
+        let action = pushtx.to_evm_action().unwrap();
+        assert_eq!(action.account.to_string(), "eosio.evm");
+        assert_eq!(action.name.to_string(), "pushtx");
+        assert_eq!(action.authorization, vec![PermissionLevel::from_str("eosio", "active").unwrap()]);
+        assert_eq!(
+            hex::encode(&action.data),
+            "0000000000ea30556ef86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+        assert_eq!(EvmPushTx::read(&action.data, &mut 0).unwrap(), pushtx);
+    }
+
+    #[test]
+    fn evm_call_should_work() {
+        let call = EvmCall::new(
+            AccountName::from_str("alice").unwrap(),
+            Address::from_str("0x3535353535353535353535353535353535353535").unwrap(),
+            1_000_000_000_000_000_000,
+            hex::decode("a9059cbb").unwrap(),
+            50000,
+        );
+        let action = call.to_evm_action().unwrap();
+        assert_eq!(action.account.to_string(), "eosio.evm");
+        assert_eq!(action.name.to_string(), "call");
+        assert_eq!(
+            hex::encode(&action.data),
+            "0000000000855c34143535353535353535353535353535353535353535200000000000000000000000000000000000000000000000000de0b6b3a764000004a9059cbb50c3000000000000"
+        );
+    }
+}
@@ -1,9 +1,17 @@
+use alloc::vec;
 use alloc::vec::Vec;
-use crate::{Read, Write, NumBytes};
+use crate::{Checksum256, NumBytes, ProducerAuthoritySchedule, Read, Signature, Write};
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
+/// Header extension `protocol_feature_activation`.
+pub const PROTOCOL_FEATURE_ACTIVATION: u16 = 0;
+/// Header extension `producer_schedule_change_extension`.
+pub const PRODUCER_SCHEDULE_CHANGE_EXTENSION: u16 = 1;
+/// Block extension `additional_block_signatures_extension`.
+pub const ADDITIONAL_BLOCK_SIGNATURES_EXTENSION: u16 = 2;
+
 #[derive(Read, Write, NumBytes, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash, Default, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
 #[eosio_core_root_path = "crate"]
@@ -15,3 +23,225 @@ impl core::fmt::Display for Extension {
         write!(f, "{}, {}", self.0, hex::encode(&self.1))
     }
 }
+
+impl Extension {
+    fn pack<T: NumBytes + Write>(id: u16, value: &T) -> crate::Result<Self> {
+        let mut data = vec![0u8; value.num_bytes()];
+        value.write(&mut data, &mut 0).map_err(crate::Error::BytesWriteError)?;
+        Ok(Extension(id, data))
+    }
+
+    fn unpack<T: Read>(&self) -> Result<T, ExtensionError> {
+        T::read(&self.1, &mut 0).map_err(|_| ExtensionError::InvalidData(self.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionError {
+    /// Extensions are not sorted by id, carries the first id out of order
+    NotInOrder(u16),
+    /// An extension that may appear once is repeated
+    Duplicate(u16),
+    /// The data of a known extension can't be unpacked
+    InvalidData(u16),
+}
+
+impl From<ExtensionError> for crate::Error {
+    fn from(e: ExtensionError) -> crate::Error {
+        crate::Error::ExtensionError(e)
+    }
+}
+
+/// Extensions must be sorted by id and the known ones may not repeat, unknown
+/// ones are allowed to repeat as we can't tell.
+fn validate_order(extensions: &[Extension], known_ids: &[u16]) -> Result<(), ExtensionError> {
+    for pair in extensions.windows(2) {
+        let (previous, id) = (pair[0].0, pair[1].0);
+        if id < previous {
+            return Err(ExtensionError::NotInOrder(id));
+        }
+        if id == previous && known_ids.contains(&id) {
+            return Err(ExtensionError::Duplicate(id));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct ProtocolFeatureActivation {
+    /// Digests of the protocol features activated by the block
+    pub protocol_features: Vec<Checksum256>,
+}
+
+/// A decoded entry of `header_extensions`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HeaderExtension {
+    ProtocolFeatureActivation(ProtocolFeatureActivation),
+    ProducerScheduleChange(ProducerAuthoritySchedule),
+    /// An extension this crate doesn't know, kept as it is
+    Unknown(Extension),
+}
+
+impl HeaderExtension {
+    pub fn id(&self) -> u16 {
+        match self {
+            HeaderExtension::ProtocolFeatureActivation(_) => PROTOCOL_FEATURE_ACTIVATION,
+            HeaderExtension::ProducerScheduleChange(_) => PRODUCER_SCHEDULE_CHANGE_EXTENSION,
+            HeaderExtension::Unknown(ext) => ext.0,
+        }
+    }
+
+    pub fn from_extension(ext: &Extension) -> crate::Result<Self> {
+        let extension = match ext.0 {
+            PROTOCOL_FEATURE_ACTIVATION => HeaderExtension::ProtocolFeatureActivation(ext.unpack()?),
+            PRODUCER_SCHEDULE_CHANGE_EXTENSION => HeaderExtension::ProducerScheduleChange(ext.unpack()?),
+            _ => HeaderExtension::Unknown(ext.clone()),
+        };
+        Ok(extension)
+    }
+
+    pub fn to_extension(&self) -> crate::Result<Extension> {
+        match self {
+            HeaderExtension::ProtocolFeatureActivation(pfa) => Extension::pack(PROTOCOL_FEATURE_ACTIVATION, pfa),
+            HeaderExtension::ProducerScheduleChange(schedule) => {
+                Extension::pack(PRODUCER_SCHEDULE_CHANGE_EXTENSION, schedule)
+            }
+            HeaderExtension::Unknown(ext) => Ok(ext.clone()),
+        }
+    }
+
+    /// Decode `header_extensions` checking the order and duplicate rules of nodeos.
+    pub fn validate_and_extract(extensions: &[Extension]) -> crate::Result<Vec<Self>> {
+        validate_order(extensions, &[PROTOCOL_FEATURE_ACTIVATION, PRODUCER_SCHEDULE_CHANGE_EXTENSION])?;
+        extensions.iter().map(HeaderExtension::from_extension).collect()
+    }
+}
+
+/// A decoded entry of `block_extensions`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockExtension {
+    /// Signatures of a block beyond `producer_signature`, never empty nor repeated
+    AdditionalBlockSignatures(Vec<Signature>),
+    /// An extension this crate doesn't know, kept as it is
+    Unknown(Extension),
+}
+
+impl BlockExtension {
+    pub fn id(&self) -> u16 {
+        match self {
+            BlockExtension::AdditionalBlockSignatures(_) => ADDITIONAL_BLOCK_SIGNATURES_EXTENSION,
+            BlockExtension::Unknown(ext) => ext.0,
+        }
+    }
+
+    pub fn from_extension(ext: &Extension) -> crate::Result<Self> {
+        let extension = match ext.0 {
+            ADDITIONAL_BLOCK_SIGNATURES_EXTENSION => {
+                let signatures: Vec<Signature> = ext.unpack()?;
+                let repeated = signatures.iter().enumerate()
+                    .any(|(i, sig)| signatures[..i].iter().any(|other| other.data[..] == sig.data[..]));
+                if signatures.is_empty() || repeated {
+                    return Err(ExtensionError::InvalidData(ext.0).into());
+                }
+                BlockExtension::AdditionalBlockSignatures(signatures)
+            }
+            _ => BlockExtension::Unknown(ext.clone()),
+        };
+        Ok(extension)
+    }
+
+    pub fn to_extension(&self) -> crate::Result<Extension> {
+        match self {
+            BlockExtension::AdditionalBlockSignatures(signatures) => {
+                Extension::pack(ADDITIONAL_BLOCK_SIGNATURES_EXTENSION, signatures)
+            }
+            BlockExtension::Unknown(ext) => Ok(ext.clone()),
+        }
+    }
+
+    /// Decode `block_extensions` checking the order and duplicate rules of nodeos.
+    pub fn validate_and_extract(extensions: &[Extension]) -> crate::Result<Vec<Self>> {
+        validate_order(extensions, &[ADDITIONAL_BLOCK_SIGNATURES_EXTENSION])?;
+        extensions.iter().map(BlockExtension::from_extension).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn assert_extension_error<T: core::fmt::Debug>(result: crate::Result<T>, expected: ExtensionError) {
+        match result {
+            Err(crate::Error::ExtensionError(e)) => assert_eq!(e, expected),
+            r => panic!("expect {:?}, got {:?}", expected, r),
+        }
+    }
+
+    #[test]
+    fn header_extensions_should_work() {
+        let pfa = ProtocolFeatureActivation {
+            protocol_features: vec![Checksum256::from_str("0ec7e080177b2c02b278d5088611686b49d739925a92d9bfcacd7fc6b74053bd").unwrap()],
+        };
+        let schedule = ProducerAuthoritySchedule::default();
+        let typed = vec![
+            HeaderExtension::ProtocolFeatureActivation(pfa),
+            HeaderExtension::ProducerScheduleChange(schedule),
+            HeaderExtension::Unknown(Extension(7, vec![1, 2, 3])),
+        ];
+        let extensions: Vec<Extension> = typed.iter().map(|ext| ext.to_extension().unwrap()).collect();
+        assert_eq!(
+            hex::encode(&extensions[0].1),
+            "010ec7e080177b2c02b278d5088611686b49d739925a92d9bfcacd7fc6b74053bd"
+        );
+        assert_eq!(extensions[1].0, PRODUCER_SCHEDULE_CHANGE_EXTENSION);
+        assert_eq!(extensions[2], Extension(7, vec![1, 2, 3]));
+
+        let decoded = HeaderExtension::validate_and_extract(&extensions).unwrap();
+        assert_eq!(decoded, typed);
+        assert_eq!(decoded.iter().map(HeaderExtension::id).collect::<Vec<_>>(), vec![0, 1, 7]);
+        assert!(HeaderExtension::validate_and_extract(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn header_extensions_should_follow_nodeos_rules() {
+        let pfa = Extension(PROTOCOL_FEATURE_ACTIVATION, vec![0]);
+        let unknown = Extension(9, vec![]);
+
+        let reversed = [Extension(PRODUCER_SCHEDULE_CHANGE_EXTENSION, vec![0, 0, 0, 0, 0]), pfa.clone()];
+        assert_extension_error(HeaderExtension::validate_and_extract(&reversed), ExtensionError::NotInOrder(0));
+        let repeated = [pfa.clone(), pfa.clone()];
+        assert_extension_error(HeaderExtension::validate_and_extract(&repeated), ExtensionError::Duplicate(0));
+        let truncated = [Extension(PROTOCOL_FEATURE_ACTIVATION, vec![1, 0])];
+        assert_extension_error(HeaderExtension::validate_and_extract(&truncated), ExtensionError::InvalidData(0));
+
+        let decoded = HeaderExtension::validate_and_extract(&[pfa, unknown.clone(), unknown.clone()]).unwrap();
+        assert_eq!(decoded[2], HeaderExtension::Unknown(unknown));
+    }
+
+    #[test]
+    fn block_extensions_should_work() {
+        let sk = keys::secret::SecretKey::from_wif(crate::test_utils::WIFS[0]).unwrap();
+        let sig: Signature = sk.sign_hash(&[1u8; 32]).unwrap().into();
+        let other: Signature = sk.sign_hash(&[2u8; 32]).unwrap().into();
+
+        let ext = BlockExtension::AdditionalBlockSignatures(vec![sig.clone(), other.clone()]).to_extension().unwrap();
+        assert_eq!(ext.0, ADDITIONAL_BLOCK_SIGNATURES_EXTENSION);
+        assert_eq!(ext.1.len(), 1 + 2 * 66);
+        match BlockExtension::validate_and_extract(&[ext.clone(), Extension(3, vec![])]).unwrap().as_slice() {
+            [BlockExtension::AdditionalBlockSignatures(signatures), BlockExtension::Unknown(unknown)] => {
+                assert_eq!(signatures[1].to_bytes()[..], other.to_bytes()[..]);
+                assert_eq!(unknown.0, 3);
+            }
+            decoded => panic!("unexpected extensions {:?}", decoded),
+        }
+
+        assert_extension_error(BlockExtension::validate_and_extract(&[ext.clone(), ext]), ExtensionError::Duplicate(2));
+        let empty = BlockExtension::AdditionalBlockSignatures(vec![]).to_extension().unwrap();
+        assert_extension_error(BlockExtension::from_extension(&empty), ExtensionError::InvalidData(2));
+        let repeated = BlockExtension::AdditionalBlockSignatures(vec![sig.clone(), sig]).to_extension().unwrap();
+        assert_extension_error(BlockExtension::from_extension(&repeated), ExtensionError::InvalidData(2));
+    }
+}
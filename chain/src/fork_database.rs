@@ -0,0 +1,352 @@
+//! Keep the reversible blocks received from one or more nodes, like the
+//! `fork_database` of nodeos, and follow the best branch among them.
+//! <https://github.com/EOSIO/eos/blob/master/libraries/chain/fork_database.cpp>
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{BlockHeaderState, Checksum256, SignedBlock, SignedBlockHeader};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForkDatabaseError {
+    /// The previous block is neither the root nor a block of the database
+    Unlinkable,
+    /// The block is already in the database
+    DuplicateBlock,
+    /// There is no block with this id in the database
+    UnknownBlock,
+}
+
+impl From<ForkDatabaseError> for crate::Error {
+    fn from(e: ForkDatabaseError) -> crate::Error {
+        crate::Error::ForkDatabaseError(e)
+    }
+}
+
+/// How adding a block changed the head.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForkEvent {
+    /// The block is stored on a branch that is not the best one
+    Stored,
+    /// The block extends the head
+    NewHead(Checksum256),
+    /// The best branch changed, `removed` lists the blocks of the old branch from the
+    /// old head down to the fork point and `applied` the ones of the new branch from
+    /// the fork point up to the new head
+    SwitchFork {
+        removed: Vec<Checksum256>,
+        applied: Vec<Checksum256>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ForkEntry {
+    state: BlockHeaderState,
+    block: Option<SignedBlock>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForkDatabase {
+    /// The last irreversible block, every block of the database descends from it
+    root: BlockHeaderState,
+    index: BTreeMap<Checksum256, ForkEntry>,
+    head: Checksum256,
+}
+
+impl ForkDatabase {
+    pub fn new(root: BlockHeaderState) -> Self {
+        ForkDatabase {
+            head: root.id,
+            root,
+            index: BTreeMap::new(),
+        }
+    }
+
+    pub fn root(&self) -> &BlockHeaderState {
+        &self.root
+    }
+
+    pub fn head(&self) -> &BlockHeaderState {
+        self.get(self.head).unwrap_or(&self.root)
+    }
+
+    /// The state of a reversible block or of the root.
+    pub fn get(&self, id: Checksum256) -> Option<&BlockHeaderState> {
+        if id == self.root.id {
+            return Some(&self.root);
+        }
+        self.index.get(&id).map(|entry| &entry.state)
+    }
+
+    /// The block added by `add_block`.
+    pub fn get_block(&self, id: Checksum256) -> Option<&SignedBlock> {
+        self.index.get(&id).and_then(|entry| entry.block.as_ref())
+    }
+
+    /// Number of reversible blocks, the root is not counted.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Validate a header against the state of its previous block and store it.
+    pub fn add(&mut self, header: &SignedBlockHeader) -> crate::Result<ForkEvent> {
+        let state = self.previous_state(header)?.next(header)?;
+        self.insert(ForkEntry { state, block: None })
+    }
+
+    /// Same as `add` for a full block, its additional signatures are verified too.
+    pub fn add_block(&mut self, block: SignedBlock) -> crate::Result<ForkEvent> {
+        let state = self.previous_state(&block.signed_block_header)?.next_block(&block)?;
+        self.insert(ForkEntry { state, block: Some(block) })
+    }
+
+    fn previous_state(&self, header: &SignedBlockHeader) -> crate::Result<&BlockHeaderState> {
+        if self.get(header.id()?).is_some() {
+            return Err(ForkDatabaseError::DuplicateBlock.into());
+        }
+        self.get(header.block_header.previous).ok_or_else(|| ForkDatabaseError::Unlinkable.into())
+    }
+
+    fn insert(&mut self, entry: ForkEntry) -> crate::Result<ForkEvent> {
+        let id = entry.state.id;
+        let previous = entry.state.header.block_header.previous;
+        let is_best = is_preferred(&entry.state, self.head());
+        self.index.insert(id, entry);
+        if !is_best {
+            return Ok(ForkEvent::Stored);
+        }
+
+        let old_head = self.head;
+        self.head = id;
+        let event = if previous == old_head {
+            ForkEvent::NewHead(id)
+        } else {
+            let (mut applied, removed) = self.fetch_branch_from(id, old_head)?;
+            applied.reverse();
+            ForkEvent::SwitchFork { removed, applied }
+        };
+
+        let lib = self.head().dpos_irreversible_blocknum;
+        if lib > self.root.block_num {
+            let new_root = self.fetch_branch(self.head, lib)?.last().cloned()
+                .ok_or(ForkDatabaseError::UnknownBlock)?;
+            self.advance_root(new_root)?;
+        }
+        Ok(event)
+    }
+
+    /// The ids of the blocks from `id` down to the block `block_num`, excluding the root.
+    pub fn fetch_branch(&self, id: Checksum256, block_num: u32) -> crate::Result<Vec<Checksum256>> {
+        let mut branch = Vec::new();
+        let mut current = self.index.get(&id).ok_or(ForkDatabaseError::UnknownBlock)?;
+        while current.state.block_num >= block_num {
+            branch.push(current.state.id);
+            match self.index.get(&current.state.header.block_header.previous) {
+                Some(entry) => current = entry,
+                None => break,
+            }
+        }
+        Ok(branch)
+    }
+
+    /// The two branches from `first` and `second` down to their common ancestor, which
+    /// is left out.
+    pub fn fetch_branch_from(
+        &self,
+        first: Checksum256,
+        second: Checksum256,
+    ) -> crate::Result<(Vec<Checksum256>, Vec<Checksum256>)> {
+        let mut first_branch = Vec::new();
+        let mut second_branch = Vec::new();
+        let mut first = self.get(first).ok_or(ForkDatabaseError::UnknownBlock)?;
+        let mut second = self.get(second).ok_or(ForkDatabaseError::UnknownBlock)?;
+
+        while first.block_num > second.block_num {
+            first_branch.push(first.id);
+            first = self.get(first.header.block_header.previous).ok_or(ForkDatabaseError::UnknownBlock)?;
+        }
+        while second.block_num > first.block_num {
+            second_branch.push(second.id);
+            second = self.get(second.header.block_header.previous).ok_or(ForkDatabaseError::UnknownBlock)?;
+        }
+        while first.id != second.id {
+            first_branch.push(first.id);
+            second_branch.push(second.id);
+            first = self.get(first.header.block_header.previous).ok_or(ForkDatabaseError::UnknownBlock)?;
+            second = self.get(second.header.block_header.previous).ok_or(ForkDatabaseError::UnknownBlock)?;
+        }
+
+        Ok((first_branch, second_branch))
+    }
+
+    /// Make block `id` the root, the blocks not descending from it are removed.
+    pub fn advance_root(&mut self, id: Checksum256) -> crate::Result<()> {
+        let new_root = self.index.get(&id).ok_or(ForkDatabaseError::UnknownBlock)?.state.clone();
+        let pruned: Vec<Checksum256> = self.index.keys()
+            .filter(|other| **other == id || !self.descends_from(**other, &new_root))
+            .cloned()
+            .collect();
+        for other in pruned.iter() {
+            self.index.remove(other);
+        }
+        self.root = new_root;
+        if !self.index.contains_key(&self.head) {
+            self.head = self.root.id;
+        }
+        Ok(())
+    }
+
+    fn descends_from(&self, mut id: Checksum256, ancestor: &BlockHeaderState) -> bool {
+        while let Some(entry) = self.index.get(&id) {
+            if entry.state.block_num <= ancestor.block_num {
+                return id == ancestor.id;
+            }
+            id = entry.state.header.block_header.previous;
+        }
+        false
+    }
+}
+
+/// Whether `state` is a better head than `head`: highest last irreversible block, then
+/// highest block number, then lowest id.
+fn is_preferred(state: &BlockHeaderState, head: &BlockHeaderState) -> bool {
+    (state.dpos_irreversible_blocknum, state.block_num) > (head.dpos_irreversible_blocknum, head.block_num)
+        || ((state.dpos_irreversible_blocknum, state.block_num) == (head.dpos_irreversible_blocknum, head.block_num)
+            && state.id < head.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::BlockTimestamp;
+    use crate::test_utils::{genesis, PRODUCERS};
+
+    /// A database rooted at a genesis block scheduling the first `producers` producers.
+    fn fork_database(producers: usize) -> ForkDatabase {
+        ForkDatabase::new(genesis(&PRODUCERS[..producers]))
+    }
+
+    /// A header on top of `previous` in `slot`, signed by the scheduled producer.
+    fn produce(db: &ForkDatabase, previous: Checksum256, slot: u32) -> SignedBlockHeader {
+        crate::test_utils::produce(db.get(previous).unwrap(), BlockTimestamp(slot), None)
+    }
+
+    fn add(db: &mut ForkDatabase, previous: Checksum256, slot: u32) -> (Checksum256, ForkEvent) {
+        let header = produce(db, previous, slot);
+        let event = db.add(&header).unwrap();
+        (header.id().unwrap(), event)
+    }
+
+    /// Headers on top of `previous` in `slots`, produced without adding them to `db`.
+    fn branch(db: &ForkDatabase, previous: Checksum256, slots: &[u32]) -> Vec<SignedBlockHeader> {
+        let mut state = db.get(previous).unwrap().clone();
+        slots.iter().map(|slot| {
+            let header = crate::test_utils::produce(&state, BlockTimestamp(*slot), None);
+            state = state.next(&header).unwrap();
+            header
+        }).collect()
+    }
+
+    fn ids(headers: &[SignedBlockHeader]) -> Vec<Checksum256> {
+        headers.iter().map(|header| header.id().unwrap()).collect()
+    }
+
+    #[test]
+    fn fork_database_should_switch_forks() {
+        // a single producer out of three never makes blocks irreversible
+        let mut db = fork_database(3);
+        let genesis = db.root().id;
+        let (a2, event) = add(&mut db, genesis, 1_001);
+        assert_eq!(event, ForkEvent::NewHead(a2));
+
+        // two branches of the same height, on the same height the lowest id wins
+        let mut branches = [branch(&db, a2, &[1_002, 1_003]), branch(&db, a2, &[1_004, 1_005])];
+        branches.sort_by_key(|headers| headers[1].id().unwrap());
+        let [best, other] = branches;
+        let (best_ids, other_ids) = (ids(&best), ids(&other));
+        let mut other_first = db.clone();
+        let mut best_first = db;
+
+        assert_eq!(other_first.add(&other[0]).unwrap(), ForkEvent::NewHead(other_ids[0]));
+        assert_eq!(other_first.add(&other[1]).unwrap(), ForkEvent::NewHead(other_ids[1]));
+        // a shorter branch doesn't change the head
+        assert_eq!(other_first.add(&best[0]).unwrap(), ForkEvent::Stored);
+        assert_eq!(other_first.head().id, other_ids[1]);
+        assert_eq!(
+            other_first.add(&best[1]).unwrap(),
+            ForkEvent::SwitchFork { removed: vec![other_ids[1], other_ids[0]], applied: best_ids.clone() },
+        );
+        assert_eq!(other_first.head().id, best_ids[1]);
+
+        assert_eq!(best_first.add(&best[0]).unwrap(), ForkEvent::NewHead(best_ids[0]));
+        assert_eq!(best_first.add(&best[1]).unwrap(), ForkEvent::NewHead(best_ids[1]));
+        assert_eq!(best_first.add(&other[0]).unwrap(), ForkEvent::Stored);
+        assert_eq!(best_first.add(&other[1]).unwrap(), ForkEvent::Stored);
+        assert_eq!(best_first.head().id, best_ids[1]);
+
+        // the longer branch wins whatever its ids
+        let (o5, event) = add(&mut best_first, other_ids[1], 1_006);
+        assert_eq!(
+            event,
+            ForkEvent::SwitchFork { removed: vec![best_ids[1], best_ids[0]], applied: vec![other_ids[0], other_ids[1], o5] },
+        );
+        assert_eq!(best_first.head().id, o5);
+        assert_eq!(best_first.head().block_num, 5);
+        assert_eq!(best_first.root().id, genesis);
+        assert_eq!(best_first.len(), 6);
+
+        let (b5, event) = add(&mut other_first, best_ids[1], 1_006);
+        assert_eq!(event, ForkEvent::NewHead(b5));
+
+        assert_eq!(
+            best_first.fetch_branch_from(best_ids[1], o5).unwrap(),
+            (vec![best_ids[1], best_ids[0]], vec![o5, other_ids[1], other_ids[0]]),
+        );
+        assert_eq!(best_first.fetch_branch(o5, 3).unwrap(), vec![o5, other_ids[1], other_ids[0]]);
+    }
+
+    #[test]
+    fn fork_database_should_prune_irreversible_blocks() {
+        // a single producer makes its blocks irreversible right away
+        let mut db = fork_database(1);
+        let genesis = db.root().id;
+
+        let (a2, _) = add(&mut db, genesis, 1_001);
+        let fork = produce(&db, genesis, 1_002);
+        db.add(&fork).unwrap();
+        assert_eq!(db.len(), 2);
+
+        let (a3, _) = add(&mut db, a2, 1_003);
+        let (a4, _) = add(&mut db, a3, 1_004);
+        assert_eq!(db.head().id, a4);
+        assert_eq!(db.head().dpos_irreversible_blocknum, 3);
+        assert_eq!(db.root().id, a3);
+        assert_eq!(db.len(), 1);
+        assert!(db.get(a2).is_none());
+        assert!(db.get(fork.id().unwrap()).is_none());
+
+        // blocks on pruned branches can't be linked any more
+        let late = produce(&fork_database(1), genesis, 1_005);
+        match db.add(&late) {
+            Err(crate::Error::ForkDatabaseError(ForkDatabaseError::Unlinkable)) => {}
+            _ => panic!("the genesis block is pruned"),
+        }
+        let head = db.head().header.clone();
+        match db.add(&head) {
+            Err(crate::Error::ForkDatabaseError(ForkDatabaseError::DuplicateBlock)) => {}
+            _ => panic!("the block is in the database"),
+        }
+    }
+
+    #[test]
+    fn fork_database_should_store_blocks() {
+        let mut db = fork_database(1);
+        let header = produce(&db, db.root().id, 1_001);
+        let block = SignedBlock::new(header.clone());
+        assert_eq!(db.add_block(block.clone()).unwrap(), ForkEvent::NewHead(header.id().unwrap()));
+        assert_eq!(db.get_block(header.id().unwrap()), Some(&block));
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 use alloc::vec::Vec;
-use crate::{Checksum256, make_canonical_pair};
+use crate::{Checksum256, make_canonical_pair, NumBytes, Read, ReadError, SerializeData, Write, WriteError};
+use crate::merkle::{make_canonical_left, make_canonical_right};
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -56,19 +57,22 @@ fn calculate_max_depth(node_count: u64) -> usize {
     clz_power_2(implied_count) + 1
 }
 
+/// Named `_node_count` and `_active_nodes` in the JSON of nodeos.
 #[derive(Clone, Default, Debug, PartialEq, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
 pub struct IncrementalMerkle {
-    _node_count: u64,
-    _active_nodes: Vec<Checksum256>,
+    #[cfg_attr(feature = "std", serde(rename = "_node_count"))]
+    node_count: u64,
+    #[cfg_attr(feature = "std", serde(rename = "_active_nodes"))]
+    active_nodes: Vec<Checksum256>,
 }
 
 impl IncrementalMerkle {
 
     pub fn new(node_count: u64, active_nodes: Vec<Checksum256>) -> Self {
         IncrementalMerkle {
-            _node_count: node_count,
-            _active_nodes: active_nodes,
+            node_count,
+            active_nodes,
         }
     }
 
@@ -118,11 +122,11 @@ impl IncrementalMerkle {
     // @return - the new root
     pub fn append(&mut self, digest: Checksum256) -> crate::Result<Checksum256> {
         let mut partial = false;
-        let max_depth = calculate_max_depth(self._node_count + 1);
+        let max_depth = calculate_max_depth(self.node_count + 1);
         let mut current_depth = max_depth - 1;
-        let mut index = self._node_count;
+        let mut index = self.node_count;
         let mut top = digest;
-        let mut active_iter = self._active_nodes.iter();
+        let mut active_iter = self.active_nodes.iter();
         let mut updated_active_nodes: Vec<Checksum256> = Vec::with_capacity(max_depth);
 
         while current_depth > 0 {
@@ -165,24 +169,140 @@ impl IncrementalMerkle {
         updated_active_nodes.push(top);
 
         // store the new active_nodes
-        self._active_nodes = updated_active_nodes;
+        self.active_nodes = updated_active_nodes;
 
         // update the node count
-        self._node_count += 1;
+        self.node_count += 1;
 
-        return Ok(self._active_nodes[self._active_nodes.len() - 1]);
+        Ok(self.active_nodes[self.active_nodes.len() - 1])
+    }
+
+    pub fn node_count(&self) -> u64 {
+        self.node_count
+    }
+
+    /// The nodes needed by the next append, the last one is the root.
+    pub fn active_nodes(&self) -> &[Checksum256] {
+        &self.active_nodes
+    }
+
+    /// The depth of the tree with the current node count.
+    pub fn max_depth(&self) -> usize {
+        calculate_max_depth(self.node_count)
     }
 
     // return the current root of the incremental merkle
     pub fn get_root(&self) -> Checksum256 {
-        if self._node_count > 0 {
-            return self._active_nodes[self._active_nodes.len() - 1];
+        if self.node_count > 0 {
+            self.active_nodes[self.active_nodes.len() - 1]
+        } else {
+            Default::default()
+        }
+    }
+
+    // return the roots of the fully-realized sub-trees, from the lowest to the highest,
+    // one for each bit set in the node count
+    fn full_nodes(&self) -> crate::Result<&[Checksum256]> {
+        let count = self.node_count.count_ones() as usize;
+        let len = self.active_nodes.len();
+        if self.node_count == 0 {
+            Ok(&[])
+        } else if self.node_count.is_power_of_two() && len == 1 {
+            // the root is the only fully-realized node
+            Ok(&self.active_nodes[..])
+        } else if len == count + 1 {
+            Ok(&self.active_nodes[..count])
+        } else {
+            Err(crate::Error::IncreMerkleError)
+        }
+    }
+
+    /// The merkle path of leaf `position` in the tree made by appending `leaves` to this
+    /// one, to check with `verify_proof` against the root of that tree.
+    ///
+    /// With the blockroot merkle of a checkpoint block and the ids of the blocks since,
+    /// it proves a block id to the blockroot merkle of a later block, block `n` is leaf `n - 1`.
+    /// Only the appended leaves can be proven.
+    pub fn get_proof(&self, leaves: &[Checksum256], position: u64) -> crate::Result<Vec<Checksum256>> {
+        let count = self.node_count;
+        if position < count || position - count >= leaves.len() as u64 {
+            return Err(crate::Error::IncreMerkleError);
+        }
+
+        let mut full_nodes = self.full_nodes()?.iter();
+        let mut nodes = leaves.to_vec();
+        // position of nodes[0] in the current level
+        let mut base = count;
+        let mut position = position;
+        let mut level = 0;
+        let mut paths = Vec::new();
+        loop {
+            // the left sibling of the first appended node is a fully-realized node
+            if count.checked_shr(level).unwrap_or(0) & 0x1 == 1 {
+                let left_value = full_nodes.next().ok_or(crate::Error::IncreMerkleError)?;
+                nodes.insert(0, *left_value);
+                base -= 1;
+            }
+            if base == 0 && nodes.len() == 1 {
+                break;
+            }
+            if (nodes.len() & 0x1) == 1 {
+                nodes.push(nodes[nodes.len() - 1]);
+            }
+
+            let index = (position - base) as usize;
+            if (position & 0x1) == 1 {
+                paths.push(make_canonical_left(&nodes[index - 1]));
+            } else {
+                paths.push(make_canonical_right(&nodes[index + 1]));
+            }
+
+            nodes = nodes.chunks(2)
+                .map(|pair| Checksum256::hash(make_canonical_pair(&pair[0], &pair[1])))
+                .collect::<crate::Result<Vec<Checksum256>>>()?;
+            base >>= 1;
+            position >>= 1;
+            level += 1;
+        }
+
+        Ok(paths)
+    }
+}
+
+// nodeos reflects `_active_nodes` before `_node_count`
+impl NumBytes for IncrementalMerkle {
+    fn num_bytes(&self) -> usize {
+        self.active_nodes.num_bytes() + self.node_count.num_bytes()
+    }
+}
+
+impl Read for IncrementalMerkle {
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let active_nodes = Vec::<Checksum256>::read(bytes, pos)?;
+        let node_count = u64::read(bytes, pos)?;
+        let merkle = IncrementalMerkle::new(node_count, active_nodes);
+        // the active nodes must be the ones `append` leaves for this node count
+        let valid = if node_count == 0 {
+            merkle.active_nodes.is_empty()
         } else {
-            return Default::default();
+            merkle.full_nodes().is_ok()
+        };
+        if !valid {
+            return Err(ReadError::NotSupportMessageType);
         }
+        Ok(merkle)
     }
 }
 
+impl Write for IncrementalMerkle {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.active_nodes.write(bytes, pos)?;
+        self.node_count.write(bytes, pos)
+    }
+}
+
+impl SerializeData for IncrementalMerkle {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,7 +312,7 @@ mod tests {
         assert!(ret.is_ok());
         assert_eq!(incr_merkle.get_root(), root.into());
         assert_eq!(ret.unwrap(), root.into());
-        assert_eq!(incr_merkle._node_count, node_count);
+        assert_eq!(incr_merkle.node_count(), node_count);
     }
 
     #[test]
@@ -277,6 +397,85 @@ mod tests {
         assert_merkle(&mut im, "000004b54d7733aecae89fe83bd0fdebc32c9f73046b86272f1a55da565261be", "f4a05bb8b4402a8a486720bbfb3c9db3a599ab52268eb846e1d404b2bf63323a", 1205);
     }
 
+    #[test]
+    fn incre_merkle_get_proof_should_work() {
+        let ids: Vec<Checksum256> = (0u8..20).map(|i| Checksum256::hash_from_slice(&[i])).collect();
+        for checkpoint in 0..ids.len() {
+            let mut im = IncrementalMerkle::default();
+            for id in ids[..checkpoint].iter() {
+                im.append(*id).unwrap();
+            }
+            for end in checkpoint + 1..=ids.len() {
+                let mut later = im.clone();
+                for id in ids[checkpoint..end].iter() {
+                    later.append(*id).unwrap();
+                }
+                for position in checkpoint..end {
+                    let paths = im.get_proof(&ids[checkpoint..end], position as u64).unwrap();
+                    assert_eq!(paths, crate::get_proof(position, ids[..end].to_vec()).unwrap());
+                    assert!(crate::verify_proof(&paths, ids[position], later.get_root()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn incre_merkle_get_proof_should_error() {
+        let ids: Vec<Checksum256> = (0u8..6).map(|i| Checksum256::hash_from_slice(&[i])).collect();
+        let mut im = IncrementalMerkle::default();
+        for id in ids[..3].iter() {
+            im.append(*id).unwrap();
+        }
+        // leaves before the checkpoint are not covered by the active nodes
+        assert!(im.get_proof(&ids[3..], 2).is_err());
+        assert!(im.get_proof(&ids[3..], 6).is_err());
+        assert!(im.get_proof(&[], 3).is_err());
+
+        let broken = IncrementalMerkle::new(3, vec![ids[0]]);
+        assert!(broken.get_proof(&ids[3..], 3).is_err());
+    }
+
+    #[test]
+    fn incre_merkle_binary_should_work() {
+        let mut im = IncrementalMerkle::default();
+        assert_eq!(hex::encode(im.to_serialize_data().unwrap()), "000000000000000000");
+        assert_eq!(im.max_depth(), 0);
+
+        im.append("00000001bcf2f448225d099685f14da76803028926af04d2607eafcf609c265c".into()).unwrap();
+        im.append("000000025e8d459281b82824f627a65c99ce538c0b7b5077640810834ad29550".into()).unwrap();
+        im.append("00000003a833fc0d4af3d9eefa4d84b6ec6557686a38b198512dad758d95c83a".into()).unwrap();
+        assert_eq!(im.node_count(), 3);
+        assert_eq!(im.active_nodes().len(), 3);
+        assert_eq!(im.active_nodes()[2], im.get_root());
+        assert_eq!(im.max_depth(), 3);
+
+        let data = im.to_serialize_data().unwrap();
+        assert_eq!(data.len(), im.num_bytes());
+        assert_eq!(
+            hex::encode(&data),
+            "0300000003a833fc0d4af3d9eefa4d84b6ec6557686a38b198512dad758d95c83a\
+             9e417ac4c5add67b79e648e58b64b0a18f04292421944ada38372bdf5e5caa44\
+             47fcbe87388fdb0f535051d79d25960550317dca4727984a3e63262058bf6949\
+             0300000000000000"
+        );
+        assert_eq!(IncrementalMerkle::read(&data, &mut 0).unwrap(), im);
+        assert!(IncrementalMerkle::read(&data[..data.len() - 1], &mut 0).is_err());
+
+        // active nodes that don't match the node count, e.g. no root for one node
+        // which used to panic in `get_root`
+        assert!(IncrementalMerkle::read(&hex::decode("000100000000000000").unwrap(), &mut 0).is_err());
+        assert!(IncrementalMerkle::read(&hex::decode("000300000000000000").unwrap(), &mut 0).is_err());
+        let mut data = vec![1u8];
+        data.extend_from_slice(&[0u8; 32]);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        assert!(IncrementalMerkle::read(&data, &mut 0).is_err());
+        data[33] = 1;
+        assert!(IncrementalMerkle::read(&data, &mut 0).is_ok());
+        let mut data = im.to_serialize_data().unwrap();
+        data[0] = 2;
+        assert!(IncrementalMerkle::read(&data, &mut 0).is_err());
+    }
+
     #[test]
     fn incre_merkle_serde_should_be_ok() {
         let inc_mekle_str = r#"{
@@ -298,6 +497,6 @@ mod tests {
         }"#;
         let merkle: Result<IncrementalMerkle, _> = serde_json::from_str(inc_mekle_str);
         assert!(merkle.is_ok());
-        assert_eq!(merkle.unwrap()._node_count, 31966338);
+        assert_eq!(merkle.unwrap().node_count(), 31966338);
     }
 }
@@ -2,30 +2,51 @@
 
 extern crate alloc;
 
+pub mod abi;
 pub mod action;
+pub mod action_proof;
 pub mod action_receipt;
 pub mod asset;
+pub mod authority;
+pub mod authority_checker;
+pub mod batch_verifier;
 pub mod bytes;
 pub mod block;
 pub mod block_header;
+pub mod block_header_state;
+#[cfg(feature = "std")]
+pub mod block_log;
 pub mod block_timestamp;
+pub mod blockchain_parameters;
 pub mod checksum160;
 pub mod checksum256;
 pub mod checksum512;
+pub mod deploy;
 pub mod error;
+pub mod evm;
 pub mod extension;
+pub mod fork_database;
 pub mod incremental_merkle;
+pub mod light_client;
 pub mod merkle;
+pub mod msig;
 pub mod names;
 pub mod ops;
 pub mod permission_level;
+pub mod permission_link;
 pub mod producer_key;
 pub mod producer_schedule;
 pub mod producer_schedule_v2;
 pub mod public_key;
+pub mod ship;
 pub mod signature;
+#[cfg(feature = "std")]
+pub mod snapshot;
 pub mod symbol;
 pub mod symbol_code;
+pub mod system;
+#[cfg(test)]
+mod test_utils;
 pub mod time_point;
 pub mod time_point_sec;
 pub mod transaction;
@@ -35,35 +56,52 @@ pub mod utils;
 pub use eosio_core_derive::*;
 
 pub use self::{
+    abi::*,
     action::*,
+    action_proof::*,
     action_receipt::*,
     asset::*,
+    authority::*,
+    authority_checker::*,
+    batch_verifier::*,
     bytes::*,
     block::*,
     block_header::*,
+    block_header_state::*,
     block_timestamp::*,
+    blockchain_parameters::*,
     checksum160::*,
     checksum256::*,
     checksum512::*,
+    deploy::*,
     error::*,
+    evm::*,
     incremental_merkle::*,
+    light_client::*,
     merkle::*,
+    msig::*,
     extension::*,
+    fork_database::*,
     names::*,
     ops::*,
     permission_level::*,
+    permission_link::*,
     producer_key::*,
     producer_schedule::*,
     producer_schedule_v2::*,
     public_key::*,
+    ship::*,
     signature::*,
     symbol::*,
     symbol_code::*,
+    system::*,
     time_point::*,
     time_point_sec::*,
     transaction::*,
     unsigned_int::*,
 };
+#[cfg(feature = "std")]
+pub use self::{block_log::*, snapshot::*};
 use alloc::vec;
 use alloc::vec::Vec;
 
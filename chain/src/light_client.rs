@@ -0,0 +1,309 @@
+//! Follow a chain of block headers from a trusted checkpoint without a full node.
+//!
+//! Each header must link to the previous one, be produced by the producer scheduled
+//! for its slot and carry signatures satisfying the producer's block signing authority
+//! over the blockroot merkle the client maintains. The client applies headers with
+//! `BlockHeaderState`, so it tracks the producers' confirmations and promotes a proposed
+//! schedule only once the block proposing it is irreversible, like nodeos. A header
+//! claiming the version of a schedule that is still pending is rejected.
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::{
+    BlockHeaderState, BlockHeaderStateError, Checksum256, IncrementalMerkle,
+    ProducerAuthoritySchedule, ScheduleInfo, Signature, SignedBlock, SignedBlockHeader, verify_proof
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightClient {
+    /// The state of the head block
+    state: BlockHeaderState,
+}
+
+impl LightClient {
+    /// Start from a trusted block, `blockroot_merkle` and `schedule_hash` are the
+    /// ones of its block header state, the merkle doesn't include `id` yet.
+    ///
+    /// Nothing is known about the confirmations before the checkpoint, so the last
+    /// irreversible block only moves once two thirds of the active producers confirmed
+    /// blocks after it.
+    pub fn new(
+        id: Checksum256,
+        blockroot_merkle: IncrementalMerkle,
+        active_schedule: ProducerAuthoritySchedule,
+        schedule_hash: Checksum256,
+    ) -> Self {
+        let block_num = crate::BlockHeader::num_from_id(id);
+        let producer_to_last_implied_irb: BTreeMap<_, _> = active_schedule.producers.iter()
+            .map(|producer| (producer.producer_name, 0))
+            .collect();
+        let valid_block_signing_authority = active_schedule.producers.first()
+            .map(|producer| producer.authority.clone())
+            .unwrap_or_default();
+        let state = BlockHeaderState {
+            id,
+            block_num,
+            header: SignedBlockHeader::default(),
+            dpos_proposed_irreversible_blocknum: 0,
+            dpos_irreversible_blocknum: 0,
+            pending_schedule: ScheduleInfo {
+                schedule_lib_num: block_num,
+                schedule_hash,
+                schedule: ProducerAuthoritySchedule::new(active_schedule.version, Vec::new()),
+            },
+            active_schedule,
+            blockroot_merkle,
+            producer_to_last_produced: BTreeMap::new(),
+            producer_to_last_implied_irb,
+            valid_block_signing_authority,
+            additional_signatures: Vec::new(),
+            confirm_count: Vec::new(),
+        };
+        LightClient { state }
+    }
+
+    /// A schedule proposed in block `schedule_lib_num`, at or before the checkpoint,
+    /// that is not active yet.
+    pub fn with_pending_schedule(mut self, schedule: ProducerAuthoritySchedule, schedule_lib_num: u32) -> Self {
+        self.state.pending_schedule.schedule = schedule;
+        self.state.pending_schedule.schedule_lib_num = schedule_lib_num;
+        self
+    }
+
+    pub fn head_id(&self) -> Checksum256 {
+        self.state.id
+    }
+
+    pub fn head_block_num(&self) -> u32 {
+        self.state.block_num
+    }
+
+    /// The last irreversible block as far as the client could follow the confirmations.
+    pub fn last_irreversible_block_num(&self) -> u32 {
+        self.state.dpos_irreversible_blocknum
+    }
+
+    pub fn blockroot_merkle(&self) -> &IncrementalMerkle {
+        &self.state.blockroot_merkle
+    }
+
+    pub fn active_schedule(&self) -> &ProducerAuthoritySchedule {
+        &self.state.active_schedule
+    }
+
+    pub fn pending_schedule(&self) -> Option<&ProducerAuthoritySchedule> {
+        Some(&self.state.pending_schedule.schedule).filter(|schedule| !schedule.producers.is_empty())
+    }
+
+    pub fn schedule_hash(&self) -> Checksum256 {
+        self.state.pending_schedule.schedule_hash
+    }
+
+    /// Verify the next header and make it the head, the client is left unchanged on error.
+    pub fn apply(&mut self, header: &SignedBlockHeader) -> crate::Result<Checksum256> {
+        self.apply_with_signatures(header, &[])
+    }
+
+    /// Verify the header of the next block and all its signatures.
+    pub fn apply_block(&mut self, block: &SignedBlock) -> crate::Result<Checksum256> {
+        self.apply_with_signatures(&block.signed_block_header, &block.additional_signatures()?)
+    }
+
+    /// Same as `apply` for a header signed by several keys, `additional_signatures`
+    /// are the ones of the `additional_block_signatures` block extension.
+    pub fn apply_with_signatures(
+        &mut self,
+        header: &SignedBlockHeader,
+        additional_signatures: &[Signature],
+    ) -> crate::Result<Checksum256> {
+        let h = &header.block_header;
+        if h.previous != self.state.id {
+            if h.block_num() <= self.state.block_num {
+                return Err(BlockHeaderStateError::Fork(h.block_num()).into());
+            }
+            return Err(BlockHeaderStateError::Unlinkable.into());
+        }
+
+        self.state = self.state.next_with_signatures(header, additional_signatures.to_vec())?;
+        Ok(self.state.id)
+    }
+
+    /// Check `path` proves `block_id` is the id of a block before the head, `path` goes
+    /// to the blockroot merkle the head producer signed, see `IncrementalMerkle::get_proof`.
+    pub fn verify_block_id(&self, block_id: Checksum256, path: &[Checksum256]) -> bool {
+        verify_proof(path, block_id, self.state.blockroot_merkle.get_root())
+    }
+
+    /// Apply headers in order, stopping at the first invalid one.
+    pub fn apply_all<'a, I>(&mut self, headers: I) -> crate::Result<Checksum256>
+        where I: IntoIterator<Item=&'a SignedBlockHeader>
+    {
+        for header in headers {
+            self.apply(header)?;
+        }
+        Ok(self.state.id)
+    }
+}
+
+/// Use a block header state, e.g. from `get_block_header_state`, as checkpoint.
+impl From<&BlockHeaderState> for LightClient {
+    fn from(state: &BlockHeaderState) -> Self {
+        LightClient { state: state.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+    use crate::{BlockTimestamp, ProducerSchedule};
+    use crate::test_utils::{genesis, producer, producer_secret_key, schedule, PRODUCERS};
+
+    /// Produce `count` headers with the full block header state, proposing `new_schedule`
+    /// in the second one.
+    fn produce(count: usize, new_schedule: Option<ProducerSchedule>) -> (BlockHeaderState, Vec<SignedBlockHeader>) {
+        let genesis = genesis(&PRODUCERS[..1]);
+        let mut state = genesis.clone();
+        let mut headers = Vec::new();
+        for i in 0..count {
+            let timestamp = BlockTimestamp(state.header.block_header.timestamp.as_u32() + 1);
+            let new_producers = if i == 1 { new_schedule.clone() } else { None };
+            let header = crate::test_utils::produce(&state, timestamp, new_producers);
+            state = state.next(&header).unwrap();
+            headers.push(header);
+        }
+        (genesis, headers)
+    }
+
+    #[test]
+    fn light_client_should_follow_headers() {
+        let (genesis, headers) = produce(60, Some(schedule(1, &PRODUCERS[1..])));
+        let mut client = LightClient::from(&genesis);
+
+        let head = client.apply_all(headers.iter()).unwrap();
+        assert_eq!(head, headers.last().unwrap().id().unwrap());
+        assert_eq!(client.head_block_num(), 61);
+        assert_eq!(client.active_schedule(), &ProducerAuthoritySchedule::from(schedule(1, &PRODUCERS[1..])));
+        assert_eq!(client.pending_schedule(), None);
+        assert_eq!(client.schedule_hash(), schedule(1, &PRODUCERS[1..]).schedule_hash().unwrap());
+    }
+
+    #[test]
+    fn light_client_should_promote_once_irreversible() {
+        let (genesis, headers) = produce(20, Some(schedule(1, &PRODUCERS[1..])));
+        let mut client = LightClient::from(&genesis);
+        let mut full = genesis;
+        for header in headers.iter() {
+            client.apply(header).unwrap();
+            full = full.next(header).unwrap();
+            assert_eq!(client.last_irreversible_block_num(), full.dpos_irreversible_blocknum);
+            assert_eq!(client.active_schedule(), &full.active_schedule);
+        }
+        assert_eq!(client.active_schedule().version, 1);
+    }
+
+    #[test]
+    fn light_client_should_reject_early_schedule_version() {
+        let (genesis, headers) = produce(4, Some(schedule(1, &PRODUCERS[1..])));
+        let mut client = LightClient::from(&genesis);
+        client.apply_all(headers[..2].iter()).unwrap();
+        // the schedule is proposed in block 3, which is not irreversible yet
+        assert_eq!(client.last_irreversible_block_num(), 2);
+        assert!(client.pending_schedule().is_some());
+
+        // a header announcing the pending version right away, properly signed by the
+        // producer the pending schedule has for the slot
+        let mut early = headers[2].clone();
+        let pending = client.pending_schedule().unwrap();
+        let producer = pending.get_scheduled_producer(early.block_header.timestamp).unwrap().producer_name;
+        early.block_header.schedule_version = pending.version;
+        early.block_header.producer = producer;
+        let mut merkle = client.blockroot_merkle().clone();
+        merkle.append(client.head_id()).unwrap();
+        let header_bmroot = Checksum256::hash((early.block_header.digest().unwrap(), merkle.get_root())).unwrap();
+        let digest = Checksum256::hash((header_bmroot, client.schedule_hash())).unwrap();
+        early.producer_signature = producer_secret_key(producer).sign_hash(digest.as_bytes()).unwrap().into();
+        match client.apply(&early) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::WrongProducer(p))) => assert_eq!(p, producer),
+            _ => panic!("the pending schedule should not be active before the proposal is irreversible"),
+        }
+        assert_eq!(client.active_schedule().version, 0);
+        assert_eq!(client.apply(&headers[2]).unwrap(), headers[2].id().unwrap());
+    }
+
+    #[test]
+    fn light_client_should_start_from_checkpoint() {
+        let (genesis, headers) = produce(10, None);
+        let mut full = genesis;
+        for header in headers.iter().take(5) {
+            full = full.next(header).unwrap();
+        }
+
+        let mut client = LightClient::new(
+            full.id,
+            full.blockroot_merkle.clone(),
+            full.active_schedule.clone(),
+            full.pending_schedule.schedule_hash,
+        );
+        assert_eq!(client.apply_all(headers[5..].iter()).unwrap(), headers[9].id().unwrap());
+        assert_eq!(client.head_block_num(), 11);
+    }
+
+    #[test]
+    fn light_client_should_verify_block_ids() {
+        let (genesis, headers) = produce(10, None);
+        let mut checkpoint = genesis;
+        for header in headers.iter().take(5) {
+            checkpoint = checkpoint.next(header).unwrap();
+        }
+        let mut client = LightClient::from(&checkpoint);
+        client.apply_all(headers[5..].iter()).unwrap();
+
+        // the ids of blocks 6 to 10, the blockroot merkle of the head covers blocks 1 to 10
+        let mut ids = vec![checkpoint.id];
+        ids.extend(headers[5..9].iter().map(|header| header.id().unwrap()));
+        let path = checkpoint.blockroot_merkle.get_proof(&ids, 7).unwrap();
+        assert!(client.verify_block_id(headers[6].id().unwrap(), &path));
+        assert!(!client.verify_block_id(headers[7].id().unwrap(), &path));
+
+        let path = checkpoint.blockroot_merkle.get_proof(&ids, 9).unwrap();
+        assert!(client.verify_block_id(headers[8].id().unwrap(), &path));
+    }
+
+    #[test]
+    fn light_client_should_reject_invalid_headers() {
+        let (genesis, headers) = produce(4, None);
+        let mut client = LightClient::from(&genesis);
+        client.apply_all(headers[..2].iter()).unwrap();
+
+        // a fork of an accepted block
+        let mut fork = headers[1].clone();
+        fork.block_header.action_mroot = Checksum256::hash_from_slice(b"fork");
+        match client.apply(&fork) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::Fork(3))) => {}
+            _ => panic!("a fork should be rejected"),
+        }
+
+        // skipping a block
+        match client.apply(&headers[3]) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::Unlinkable)) => {}
+            _ => panic!("headers should link"),
+        }
+
+        let mut tampered = headers[2].clone();
+        tampered.block_header.transaction_mroot = Checksum256::hash_from_slice(b"tampered");
+        match client.apply(&tampered) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::UnknownSigningKey)) => {}
+            _ => panic!("a tampered header should not verify"),
+        }
+
+        let mut wrong_producer = headers[2].clone();
+        wrong_producer.block_header.producer = producer("proda");
+        match client.apply(&wrong_producer) {
+            Err(crate::Error::BlockHeaderStateError(BlockHeaderStateError::WrongProducer(_))) => {}
+            _ => panic!("the producer should be the scheduled one"),
+        }
+
+        // the client is unchanged by the rejected headers
+        assert_eq!(client.apply(&headers[2]).unwrap(), headers[2].id().unwrap());
+    }
+}
@@ -2,13 +2,13 @@
 use alloc::vec::Vec;
 use crate::Checksum256;
 
-fn make_canonical_left(val: &Checksum256) -> Checksum256 {
+pub(crate) fn make_canonical_left(val: &Checksum256) -> Checksum256 {
     let mut canonical_l: Checksum256 = *val;
     canonical_l.set_hash0(canonical_l.hash0() & 0xFFFFFFFFFFFFFF7Fu64);
     canonical_l
 }
 
-fn  make_canonical_right(val: &Checksum256) -> Checksum256 {
+pub(crate) fn make_canonical_right(val: &Checksum256) -> Checksum256 {
     let mut canonical_r: Checksum256 = *val;
     canonical_r.set_hash0(canonical_r.hash0() | 0x0000000000000080u64);
     canonical_r
@@ -80,7 +80,7 @@ pub fn get_proof(position: usize, ids: Vec<Checksum256>) -> crate::Result<Vec<Ch
     Ok(paths)
 }
 
-pub fn verify_proof(paths: &Vec<Checksum256>, leaf: Checksum256, expected_root: Checksum256) -> bool {
+pub fn verify_proof(paths: &[Checksum256], leaf: Checksum256, expected_root: Checksum256) -> bool {
     let mut current: Checksum256 = leaf;
     let mut left: Checksum256;
     let mut right: Checksum256;
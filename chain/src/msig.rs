@@ -0,0 +1,421 @@
+//! Actions and tables of the eosio.msig contract
+//! <https://github.com/EOSIO/eosio.contracts/blob/master/contracts/eosio.msig/include/eosio.msig/eosio.msig.hpp>
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::{
+    AccountName, Action, AuthorityChecker, Checksum256, Name, NumBytes, PermissionLevel,
+    PermissionStore, Read, ReadError, SerializeData, TimePoint, ToAction, Transaction, Write,
+    WriteError
+};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The account the multisig contract is deployed to, `eosio.msig`.
+pub const MSIG_CONTRACT: u64 = 6_138_663_587_900_751_872;
+
+/// Propose a transaction that needs the approval of `requested`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Propose {
+    pub proposer: AccountName,
+    pub proposal_name: Name,
+    pub requested: Vec<PermissionLevel>,
+    pub trx: Transaction,
+}
+
+impl Propose {
+    /// Build the action against `eosio.msig`, authorized by the proposer's `active` permission.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![PermissionLevel::active(self.proposer)])
+    }
+}
+
+impl ToAction for Propose {
+    /// `propose`
+    const NAME: u64 = 12_531_646_811_867_185_152;
+}
+
+/// Approve a proposal with `level`.
+///
+/// `proposal_hash` is a binary extension, when set the approval only counts if it
+/// matches the hash of the proposed `packed_transaction`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Approve {
+    pub proposer: AccountName,
+    pub proposal_name: Name,
+    pub level: PermissionLevel,
+    pub proposal_hash: Option<Checksum256>,
+}
+
+impl Approve {
+    /// Build the action against `eosio.msig`, authorized by `level`.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![self.level])
+    }
+}
+
+impl NumBytes for Approve {
+    fn num_bytes(&self) -> usize {
+        let hash_size = self.proposal_hash.as_ref().map_or(0, |hash| hash.num_bytes());
+        self.proposer.num_bytes() + self.proposal_name.num_bytes() + self.level.num_bytes() + hash_size
+    }
+}
+
+impl Read for Approve {
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let proposer = AccountName::read(bytes, pos)?;
+        let proposal_name = Name::read(bytes, pos)?;
+        let level = PermissionLevel::read(bytes, pos)?;
+        let proposal_hash = if *pos < bytes.len() {
+            Some(Checksum256::read(bytes, pos)?)
+        } else {
+            None
+        };
+
+        Ok(Approve { proposer, proposal_name, level, proposal_hash })
+    }
+}
+
+impl Write for Approve {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.proposer.write(bytes, pos)?;
+        self.proposal_name.write(bytes, pos)?;
+        self.level.write(bytes, pos)?;
+        match self.proposal_hash {
+            Some(ref hash) => hash.write(bytes, pos),
+            None => Ok(()),
+        }
+    }
+}
+
+impl SerializeData for Approve {}
+
+impl ToAction for Approve {
+    /// `approve`
+    const NAME: u64 = 3_849_304_916_161_986_560;
+}
+
+/// Withdraw an approval given with `level`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Unapprove {
+    pub proposer: AccountName,
+    pub proposal_name: Name,
+    pub level: PermissionLevel,
+}
+
+impl Unapprove {
+    /// Build the action against `eosio.msig`, authorized by `level`.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![self.level])
+    }
+}
+
+impl ToAction for Unapprove {
+    /// `unapprove`
+    const NAME: u64 = 15_334_012_218_401_357_824;
+}
+
+/// Remove a proposal, only the proposer may do so before it expires.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Cancel {
+    pub proposer: AccountName,
+    pub proposal_name: Name,
+    pub canceler: AccountName,
+}
+
+impl Cancel {
+    /// Build the action against `eosio.msig`, authorized by the canceler's `active` permission.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![PermissionLevel::active(self.canceler)])
+    }
+}
+
+impl ToAction for Cancel {
+    /// `cancel`
+    const NAME: u64 = 4_730_614_985_703_555_072;
+}
+
+/// Execute a proposal that collected enough approvals.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Exec {
+    pub proposer: AccountName,
+    pub proposal_name: Name,
+    pub executer: AccountName,
+}
+
+impl Exec {
+    /// Build the action against `eosio.msig`, authorized by the executer's `active` permission.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![PermissionLevel::active(self.executer)])
+    }
+}
+
+impl ToAction for Exec {
+    /// `exec`
+    const NAME: u64 = 6_292_795_316_831_780_864;
+}
+
+/// Invalidate every approval `account` has given so far.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Invalidate {
+    pub account: AccountName,
+}
+
+impl Invalidate {
+    /// Build the action against `eosio.msig`, authorized by the account's `active` permission.
+    pub fn to_msig_action(&self) -> crate::Result<Action> {
+        self.to_action(MSIG_CONTRACT.into(), vec![PermissionLevel::active(self.account)])
+    }
+}
+
+impl ToAction for Invalidate {
+    /// `invalidate`
+    const NAME: u64 = 8_428_038_897_099_505_664;
+}
+
+/// A row of the `proposal` table, scoped by the proposer.
+///
+/// `earliest_exec_time` is a binary extension, it is only present in rows
+/// written by newer versions of the contract.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Proposal {
+    pub proposal_name: Name,
+    pub packed_transaction: Vec<u8>,
+    pub earliest_exec_time: Option<Option<TimePoint>>,
+}
+
+impl Proposal {
+    /// Unpack the proposed transaction.
+    pub fn transaction(&self) -> crate::Result<Transaction> {
+        Transaction::read(&self.packed_transaction, &mut 0).map_err(crate::Error::BytesReadError)
+    }
+
+    /// The hash an `approve` has to carry as `proposal_hash`.
+    pub fn transaction_hash(&self) -> Checksum256 {
+        Checksum256::hash_from_slice(&self.packed_transaction)
+    }
+}
+
+impl NumBytes for Proposal {
+    fn num_bytes(&self) -> usize {
+        let time_size = self.earliest_exec_time.as_ref().map_or(0, |time| time.num_bytes());
+        self.proposal_name.num_bytes() + self.packed_transaction.num_bytes() + time_size
+    }
+}
+
+impl Read for Proposal {
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let proposal_name = Name::read(bytes, pos)?;
+        let packed_transaction = Vec::<u8>::read(bytes, pos)?;
+        let earliest_exec_time = if *pos < bytes.len() {
+            Some(Option::<TimePoint>::read(bytes, pos)?)
+        } else {
+            None
+        };
+
+        Ok(Proposal { proposal_name, packed_transaction, earliest_exec_time })
+    }
+}
+
+impl Write for Proposal {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.proposal_name.write(bytes, pos)?;
+        self.packed_transaction.write(bytes, pos)?;
+        match self.earliest_exec_time {
+            Some(ref time) => time.write(bytes, pos),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default)]
+#[eosio_core_root_path = "crate"]
+pub struct Approval {
+    pub level: PermissionLevel,
+    pub time: TimePoint,
+}
+
+/// A row of the `approvals2` table, scoped by the proposer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default)]
+#[eosio_core_root_path = "crate"]
+pub struct ApprovalsInfo {
+    pub version: u8,
+    pub proposal_name: Name,
+    pub requested_approvals: Vec<Approval>,
+    pub provided_approvals: Vec<Approval>,
+}
+
+impl ApprovalsInfo {
+    pub fn is_approved_by(&self, level: &PermissionLevel) -> bool {
+        self.provided_approvals.iter().any(|approval| approval.level == *level)
+    }
+
+    /// Authorizations of the proposed transaction the provided approvals don't
+    /// satisfy yet, checked the way `exec` does with the permissions from `store`.
+    pub fn unsatisfied_authorizations<S: PermissionStore>(&self, trx: &Transaction, store: &S) -> Vec<PermissionLevel> {
+        let provided: Vec<PermissionLevel> = self.provided_approvals.iter().map(|approval| approval.level).collect();
+        let delay_sec = usize::from(trx.header.delay_sec.clone()) as u32;
+
+        let mut unsatisfied: Vec<PermissionLevel> = Vec::new();
+        for level in trx.actions.iter().flat_map(|action| action.authorization.iter()) {
+            let mut checker = AuthorityChecker::new(store, None, provided.iter().cloned())
+                .provided_delay_sec(delay_sec);
+            if !checker.satisfied(level) && !unsatisfied.contains(level) {
+                unsatisfied.push(*level);
+            }
+        }
+        unsatisfied
+    }
+
+    /// Whether the proposal could be executed with the approvals provided so far.
+    pub fn is_satisfied<S: PermissionStore>(&self, trx: &Transaction, store: &S) -> bool {
+        self.unsatisfied_authorizations(trx, store).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use core::str::FromStr;
+    use crate::{Authority, BuyRamBytes, PermissionLevelWeight};
+
+    const PACKED_TRX: &str = "00105e5f00000000000000000000010000000000ea305500b0cafe4873bd3e010000000000855c3400000000a8ed3232140000000000855c340000000000855c340004000000";
+
+    fn parse<T: FromStr>(s: &str) -> T where T::Err: core::fmt::Debug {
+        T::from_str(s).unwrap()
+    }
+
+    fn level(actor: &str, permission: &str) -> PermissionLevel {
+        PermissionLevel::from_str(actor, permission).unwrap()
+    }
+
+    fn transaction() -> Transaction {
+        let buyrambytes = BuyRamBytes { payer: parse("alice"), receiver: parse("alice"), bytes: 1024 };
+        let action = buyrambytes.to_action(parse("eosio"), vec![level("alice", "active")]).unwrap();
+        Transaction::new(1_600_000_000, 0, 0, vec![action])
+    }
+
+    fn assert_msig_action(action: Action, name: &str, data: &str) {
+        assert_eq!(action.account.to_string(), "eosio.msig");
+        assert_eq!(action.name.to_string(), name);
+        assert_eq!(hex::encode(&action.data), data);
+    }
+
+    #[test]
+    fn propose_should_work() {
+        let propose = Propose {
+            proposer: parse("alice"),
+            proposal_name: parse("upgrade"),
+            requested: vec![level("bob", "active"), level("carol", "active")],
+            trx: transaction(),
+        };
+        let action = propose.to_msig_action().unwrap();
+        assert_eq!(action.authorization, vec![level("alice", "active")]);
+        assert_msig_action(
+            action.clone(),
+            "propose",
+            &format!("0000000000855c3400000040257359d5020000000000000e3d00000000a8ed3232000000008048af4100000000a8ed3232{}", PACKED_TRX),
+        );
+        assert_eq!(Propose::read(&action.data, &mut 0).unwrap(), propose);
+    }
+
+    #[test]
+    fn approve_should_work() {
+        let mut approve = Approve {
+            proposer: parse("alice"),
+            proposal_name: parse("upgrade"),
+            level: level("bob", "active"),
+            proposal_hash: None,
+        };
+        let action = approve.to_msig_action().unwrap();
+        assert_eq!(action.authorization, vec![level("bob", "active")]);
+        assert_msig_action(action.clone(), "approve", "0000000000855c3400000040257359d50000000000000e3d00000000a8ed3232");
+        assert_eq!(Approve::read(&action.data, &mut 0).unwrap(), approve);
+
+        approve.proposal_hash = Some(Checksum256::hash_from_slice(&hex::decode(PACKED_TRX).unwrap()));
+        let action = approve.to_msig_action().unwrap();
+        assert_msig_action(
+            action.clone(),
+            "approve",
+            "0000000000855c3400000040257359d50000000000000e3d00000000a8ed3232ab55b3673f550d5148befbfa45793e79f810e8a1292fe105c6886c647485c6c6",
+        );
+        assert_eq!(Approve::read(&action.data, &mut 0).unwrap(), approve);
+    }
+
+    #[test]
+    fn other_msig_actions_should_work() {
+        let unapprove = Unapprove { proposer: parse("alice"), proposal_name: parse("upgrade"), level: level("bob", "active") };
+        assert_msig_action(
+            unapprove.to_msig_action().unwrap(),
+            "unapprove",
+            "0000000000855c3400000040257359d50000000000000e3d00000000a8ed3232",
+        );
+        let cancel = Cancel { proposer: parse("alice"), proposal_name: parse("upgrade"), canceler: parse("alice") };
+        assert_msig_action(cancel.to_msig_action().unwrap(), "cancel", "0000000000855c3400000040257359d50000000000855c34");
+        let exec = Exec { proposer: parse("alice"), proposal_name: parse("upgrade"), executer: parse("alice") };
+        assert_msig_action(exec.to_msig_action().unwrap(), "exec", "0000000000855c3400000040257359d50000000000855c34");
+        let invalidate = Invalidate { account: parse("bob") };
+        assert_msig_action(invalidate.to_msig_action().unwrap(), "invalidate", "0000000000000e3d");
+    }
+
+    #[test]
+    fn proposal_row_should_decode() {
+        let row = hex::decode(format!("00000040257359d546{}", PACKED_TRX)).unwrap();
+        let proposal = Proposal::read(&row, &mut 0).unwrap();
+        assert_eq!(proposal.proposal_name, parse::<Name>("upgrade"));
+        assert_eq!(proposal.earliest_exec_time, None);
+        assert_eq!(proposal.transaction().unwrap(), transaction());
+        assert_eq!(
+            proposal.transaction_hash().to_string(),
+            "ab55b3673f550d5148befbfa45793e79f810e8a1292fe105c6886c647485c6c6"
+        );
+
+        let row = hex::decode(format!("00000040257359d546{}010000a40731af0500", PACKED_TRX)).unwrap();
+        let proposal = Proposal::read(&row, &mut 0).unwrap();
+        assert_eq!(proposal.earliest_exec_time, Some(Some(TimePoint::from(1_600_000_000_000_000))));
+        let mut data = vec![0u8; proposal.num_bytes()];
+        proposal.write(&mut data, &mut 0).unwrap();
+        assert_eq!(data, row);
+    }
+
+    #[test]
+    fn approvals_should_be_checked() {
+        let row = hex::decode(
+            "0100000040257359d501000000008048af4100000000a8ed32320000000000000000010000000000000e3d00000000a8ed3232003609cc30af0500"
+        ).unwrap();
+        let mut approvals = ApprovalsInfo::read(&row, &mut 0).unwrap();
+        assert_eq!(approvals.version, 1);
+        assert_eq!(approvals.requested_approvals[0].level, level("carol", "active"));
+        assert!(approvals.is_approved_by(&level("bob", "active")));
+        assert!(!approvals.is_approved_by(&level("carol", "active")));
+
+        // alice@active is a 2 of 2 of bob@active and carol@active
+        let mut store = BTreeMap::new();
+        store.insert(level("alice", "active"), Authority::new(2, vec![], vec![
+            PermissionLevelWeight { permission: level("bob", "active"), weight: 1 },
+            PermissionLevelWeight { permission: level("carol", "active"), weight: 1 },
+        ], vec![]));
+
+        let trx = transaction();
+        assert_eq!(approvals.unsatisfied_authorizations(&trx, &store), vec![level("alice", "active")]);
+        assert!(!approvals.is_satisfied(&trx, &store));
+
+        let approval = approvals.requested_approvals.remove(0);
+        approvals.provided_approvals.push(approval);
+        assert!(approvals.is_satisfied(&trx, &store));
+    }
+}
@@ -5,6 +5,9 @@ use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
+/// The `active` permission every account has.
+pub const ACTIVE: u64 = 3_617_214_756_542_218_240;
+
 /// A permission
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Read, Write, NumBytes, Hash, PartialOrd, Ord, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
@@ -19,6 +22,11 @@ impl PermissionLevel {
         PermissionLevel { actor, permission }
     }
 
+    /// The `active` permission of `actor`.
+    pub const fn active(actor: AccountName) -> Self {
+        PermissionLevel { actor, permission: PermissionName::new(ACTIVE) }
+    }
+
     pub fn from_str<T: AsRef<str>>(actor: T, permission: T) -> crate::Result<Self> {
         let actor = AccountName::from_str(actor.as_ref()).map_err(crate::Error::from)?;
         let permission = PermissionName::from_str(permission.as_ref()).map_err(crate::Error::from)?;
@@ -52,4 +60,10 @@ mod test {
         let s: Result<PermissionLevel, _> =serde_json::from_str(s);
         assert!(s.is_ok());
     }
+
+    #[test]
+    fn active_permission_level_should_work() {
+        let alice = AccountName::from_str("alice").unwrap();
+        assert_eq!(PermissionLevel::active(alice), PermissionLevel::from_str("alice", "active").unwrap());
+    }
 }
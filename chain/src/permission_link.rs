@@ -0,0 +1,236 @@
+//! Resolve which permission an action has to be authorized with, following `linkauth` links.
+//! Mirrors `authorization_manager::lookup_minimum_permission` of nodeos.
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use crate::authority_checker::EOSIO_ANY;
+use crate::permission_level::ACTIVE;
+use crate::{
+    AccountName, ActionName, CancelDelay, DeleteAuth, LinkAuth, NumBytes, PermissionLevel,
+    PermissionName, Read, ToAction, UnlinkAuth, UpdateAuth, Write, SYSTEM_CONTRACT
+};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// `owner`
+const OWNER: u64 = 12_044_502_819_693_133_824;
+
+/// A link made by `linkauth`, an empty `message_type` covers every action of `code`.
+#[derive(Read, Write, NumBytes, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
+#[eosio_core_root_path = "crate"]
+pub struct PermissionLink {
+    pub code: AccountName,
+    pub message_type: ActionName,
+    pub required_permission: PermissionName,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionLinkError {
+    /// Native permission actions of `eosio` always require the permission being changed
+    UnlinkableAction(ActionName),
+    /// A link requires a permission the account doesn't have
+    UnknownPermission(PermissionName),
+}
+
+impl From<PermissionLinkError> for crate::Error {
+    fn from(e: PermissionLinkError) -> crate::Error {
+        crate::Error::PermissionLinkError(e)
+    }
+}
+
+/// The permission hierarchy and links of one account.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountPermissions {
+    pub account: AccountName,
+    /// Each permission with its parent, `owner` has the empty name as parent
+    parents: BTreeMap<PermissionName, PermissionName>,
+    links: BTreeMap<(AccountName, ActionName), PermissionName>,
+}
+
+impl AccountPermissions {
+    /// An account with the default `owner` and `active` permissions.
+    pub fn new(account: AccountName) -> Self {
+        let mut parents = BTreeMap::new();
+        parents.insert(OWNER.into(), PermissionName::default());
+        parents.insert(ACTIVE.into(), OWNER.into());
+
+        AccountPermissions { account, parents, links: BTreeMap::new() }
+    }
+
+    pub fn add_permission(&mut self, permission: PermissionName, parent: PermissionName) {
+        self.parents.insert(permission, parent);
+    }
+
+    /// Add a link, replacing any previous link for the same action, like `linkauth` does.
+    pub fn add_link(&mut self, link: PermissionLink) {
+        self.links.insert((link.code, link.message_type), link.required_permission);
+    }
+
+    pub fn has_permission(&self, permission: PermissionName) -> bool {
+        self.parents.contains_key(&permission)
+    }
+
+    pub fn parent(&self, permission: PermissionName) -> Option<PermissionName> {
+        self.parents.get(&permission).cloned()
+    }
+
+    pub fn links(&self) -> impl Iterator<Item=PermissionLink> + '_ {
+        self.links.iter().map(|((code, message_type), required_permission)| PermissionLink {
+            code: *code,
+            message_type: *message_type,
+            required_permission: *required_permission,
+        })
+    }
+
+    /// Links that require a permission the account doesn't have.
+    pub fn dangling_links(&self) -> Vec<PermissionLink> {
+        self.links()
+            .filter(|link| link.required_permission != PermissionName::from(EOSIO_ANY))
+            .filter(|link| !self.has_permission(link.required_permission))
+            .collect()
+    }
+
+    /// The linked permission of an action, an exact action link wins over a contract wide one.
+    pub fn lookup_linked_permission(&self, code: AccountName, action: ActionName) -> Option<PermissionName> {
+        self.links.get(&(code, action))
+            .or_else(|| self.links.get(&(code, ActionName::default())))
+            .cloned()
+    }
+
+    /// The least privileged permission allowed to authorize the action, `None` when
+    /// it is linked to `eosio.any` and any permission of the account will do.
+    pub fn lookup_minimum_permission(&self, code: AccountName, action: ActionName) -> crate::Result<Option<PermissionName>> {
+        if code == AccountName::from(SYSTEM_CONTRACT) {
+            let unlinkable = [UpdateAuth::NAME, DeleteAuth::NAME, LinkAuth::NAME, UnlinkAuth::NAME, CancelDelay::NAME];
+            if unlinkable.contains(&action.as_u64()) {
+                return Err(PermissionLinkError::UnlinkableAction(action).into());
+            }
+        }
+
+        match self.lookup_linked_permission(code, action) {
+            None => Ok(Some(ACTIVE.into())),
+            Some(permission) if permission == PermissionName::from(EOSIO_ANY) => Ok(None),
+            Some(permission) if !self.has_permission(permission) => {
+                Err(PermissionLinkError::UnknownPermission(permission).into())
+            }
+            Some(permission) => Ok(Some(permission)),
+        }
+    }
+
+    /// The `PermissionLevel` to put in the authorization of an action, `eosio.any`
+    /// links resolve to `active` which always exists.
+    pub fn resolve(&self, code: AccountName, action: ActionName) -> crate::Result<PermissionLevel> {
+        let permission = self.lookup_minimum_permission(code, action)?.unwrap_or_else(|| ACTIVE.into());
+        Ok(PermissionLevel::new(self.account, permission))
+    }
+
+    /// Whether `provided` may authorize where `required` is needed, that is when it
+    /// is `required` itself or one of its ancestors.
+    pub fn satisfies(&self, provided: PermissionName, required: PermissionName) -> bool {
+        if !self.has_permission(provided) {
+            return false;
+        }
+        let mut current = Some(required);
+        // the depth bound guards against cycles in a hand built hierarchy
+        for _ in 0..=self.parents.len() {
+            match current {
+                Some(permission) if permission == provided => return true,
+                Some(permission) => current = self.parent(permission),
+                None => return false,
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn name<T: FromStr>(s: &str) -> T where T::Err: core::fmt::Debug {
+        T::from_str(s).unwrap()
+    }
+
+    fn link(code: &str, message_type: &str, required_permission: &str) -> PermissionLink {
+        PermissionLink {
+            code: name(code),
+            message_type: name(message_type),
+            required_permission: name(required_permission),
+        }
+    }
+
+    /// owner <- active <- trading <- bot
+    fn bot_account() -> AccountPermissions {
+        let mut account = AccountPermissions::new(name("alice"));
+        account.add_permission(name("trading"), name("active"));
+        account.add_permission(name("bot"), name("trading"));
+        account.add_link(link("eosio.token", "transfer", "bot"));
+        account.add_link(link("dex", "", "trading"));
+        account.add_link(link("dex", "withdraw", "active"));
+        account.add_link(link("game", "", "eosio.any"));
+        account
+    }
+
+    #[test]
+    fn lookup_order_should_work() {
+        let account = bot_account();
+        let resolve = |code: &str, action: &str| account.resolve(name(code), name(action)).unwrap();
+        // exact action link
+        assert_eq!(resolve("eosio.token", "transfer"), PermissionLevel::from_str("alice", "bot").unwrap());
+        assert_eq!(resolve("dex", "withdraw"), PermissionLevel::from_str("alice", "active").unwrap());
+        // contract wide link
+        assert_eq!(resolve("dex", "trade"), PermissionLevel::from_str("alice", "trading").unwrap());
+        // default
+        assert_eq!(resolve("eosio.token", "open"), PermissionLevel::from_str("alice", "active").unwrap());
+        // eosio.any
+        assert_eq!(account.lookup_minimum_permission(name("game"), name("play")).unwrap(), None);
+        assert_eq!(resolve("game", "play"), PermissionLevel::from_str("alice", "active").unwrap());
+    }
+
+    #[test]
+    fn unlinkable_actions_should_error() {
+        let account = bot_account();
+        match account.resolve(name("eosio"), name("updateauth")) {
+            Err(crate::Error::PermissionLinkError(PermissionLinkError::UnlinkableAction(action))) => {
+                assert_eq!(action, name::<ActionName>("updateauth"));
+            }
+            _ => panic!("updateauth can't be linked"),
+        }
+        assert!(account.resolve(name("eosio"), name("buyram")).is_ok());
+    }
+
+    #[test]
+    fn dangling_links_should_be_flagged() {
+        let mut account = bot_account();
+        assert!(account.dangling_links().is_empty());
+
+        account.add_link(link("eosio.token", "", "removed"));
+        assert_eq!(account.dangling_links(), vec![link("eosio.token", "", "removed")]);
+        match account.resolve(name("eosio.token"), name("open")) {
+            Err(crate::Error::PermissionLinkError(PermissionLinkError::UnknownPermission(permission))) => {
+                assert_eq!(permission, name::<PermissionName>("removed"));
+            }
+            _ => panic!("link to a missing permission should error"),
+        }
+    }
+
+    #[test]
+    fn satisfies_should_walk_parents() {
+        let account = bot_account();
+        assert!(account.satisfies(name("bot"), name("bot")));
+        assert!(account.satisfies(name("trading"), name("bot")));
+        assert!(account.satisfies(name("owner"), name("bot")));
+        assert!(!account.satisfies(name("bot"), name("trading")));
+        assert!(!account.satisfies(name("removed"), name("bot")));
+    }
+
+    #[test]
+    fn permission_link_serialize_should_work() {
+        let link = link("eosio.token", "transfer", "bot");
+        let mut data = vec![0u8; link.num_bytes()];
+        link.write(&mut data, &mut 0).unwrap();
+        assert_eq!(hex::encode(&data), "00a6823403ea3055000000572d3ccdcd000000000000323d");
+        assert_eq!(PermissionLink::read(&data, &mut 0).unwrap(), link);
+    }
+}
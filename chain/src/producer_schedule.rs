@@ -1,6 +1,6 @@
 //! <https://github.com/EOSIO/eosio.cdt/blob/796ff8bee9a0fc864f665a0a4d018e0ff18ac383/libraries/eosiolib/contracts/eosio/producer_schedule.hpp#L54-L69>
 use alloc::vec::Vec;
-use crate::{AccountName, NumBytes, ProducerKey, Read, Write, PublicKey, Checksum256, UnsignedInt};
+use crate::{AccountName, BlockTimestamp, NumBytes, ProducerKey, Read, Write, PublicKey, Checksum256, UnsignedInt};
 use codec::{Encode, Decode};
 use core::default::Default;
 #[cfg(feature = "std")]
@@ -37,6 +37,17 @@ impl ProducerSchedule {
         Default::default()
     }
 
+    /// The producer scheduled for slot `timestamp`.
+    pub fn get_scheduled_producer(&self, timestamp: BlockTimestamp) -> Option<&ProducerKey> {
+        timestamp.producer_index(self.producers.len()).map(|index| &self.producers[index])
+    }
+
+    /// First slot, at or after `timestamp`, starting a round of producer `p`.
+    pub fn next_round_start_of(&self, p: AccountName, timestamp: BlockTimestamp) -> Option<BlockTimestamp> {
+        let index = self.producers.iter().position(|producer| producer.producer_name == p)?;
+        timestamp.next_round_start_of(index, self.producers.len())
+    }
+
     pub fn schedule_hash(&self) -> crate::Result<Checksum256> {
         Checksum256::hash(self.clone())
     }
@@ -4,7 +4,10 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::{convert::From, str::FromStr};
-use crate::{AccountName, NumBytes, Read, Write, PublicKey, Checksum256, UnsignedInt};
+use crate::{
+    AccountName, BlockHeaderStateError, BlockTimestamp, KeyWeight, NumBytes, ProducerKey, ProducerSchedule, Read,
+    Write, PublicKey, Checksum256, Signature, UnsignedInt
+};
 use codec::{Encode, Decode};
 use core::default::Default;
 #[cfg(feature = "std")]
@@ -120,15 +123,6 @@ pub struct BlockSigningAuthority (
     pub BlockSigningAuthorityV0,
 );
 
-#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq, Encode, Decode)]
-#[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
-#[eosio_core_root_path = "crate"]
-#[repr(C)]
-pub struct KeyWeight {
-    pub key: PublicKey,
-    pub weight: u16,
-}
-
 impl ProducerAuthoritySchedule {
     pub fn new(version: u32, producers: Vec<ProducerAuthority>) -> Self {
         Self {
@@ -137,6 +131,8 @@ impl ProducerAuthoritySchedule {
         }
     }
 
+    /// The first key of the producer authority, use `get_producer_authority` to
+    /// verify blocks of producers signing with several keys.
     pub fn get_producer_key(&self, p: AccountName) -> PublicKey {
         for i in self.producers.iter() {
             if i.producer_name == p {
@@ -149,9 +145,136 @@ impl ProducerAuthoritySchedule {
         Default::default()
     }
 
+    pub fn get_producer_authority(&self, p: AccountName) -> Option<&BlockSigningAuthority> {
+        self.producers.iter()
+            .find(|producer| producer.producer_name == p)
+            .map(|producer| &producer.authority)
+    }
+
+    /// The producer scheduled for slot `timestamp`.
+    pub fn get_scheduled_producer(&self, timestamp: BlockTimestamp) -> Option<&ProducerAuthority> {
+        timestamp.producer_index(self.producers.len()).map(|index| &self.producers[index])
+    }
+
+    /// First slot, at or after `timestamp`, starting a round of producer `p`.
+    pub fn next_round_start_of(&self, p: AccountName, timestamp: BlockTimestamp) -> Option<BlockTimestamp> {
+        let index = self.producers.iter().position(|producer| producer.producer_name == p)?;
+        timestamp.next_round_start_of(index, self.producers.len())
+    }
+
     pub fn schedule_hash(&self) -> crate::Result<Checksum256> {
         Checksum256::hash(self.clone())
     }
+
+    /// What changes from this schedule to `new`, convert legacy schedules with `From`
+    /// to compare them.
+    pub fn diff(&self, new: &ProducerAuthoritySchedule) -> ScheduleDiff {
+        let find = |producers: &[ProducerAuthority], name: AccountName| {
+            producers.iter().find(|producer| producer.producer_name == name).cloned()
+        };
+        let mut diff = ScheduleDiff::default();
+        for old_producer in self.producers.iter() {
+            match find(&new.producers, old_producer.producer_name) {
+                None => diff.removed.push(old_producer.clone()),
+                Some(new_producer) => if new_producer.authority != old_producer.authority {
+                    diff.key_changed.push((old_producer.clone(), new_producer));
+                },
+            }
+        }
+        for new_producer in new.producers.iter() {
+            if find(&self.producers, new_producer.producer_name).is_none() {
+                diff.added.push(new_producer.clone());
+            }
+        }
+
+        // the producers of both schedules, each in the order of its schedule
+        let kept = |from: &[ProducerAuthority], other: &[ProducerAuthority]| -> Vec<AccountName> {
+            from.iter()
+                .map(|producer| producer.producer_name)
+                .filter(|name| other.iter().any(|producer| producer.producer_name == *name))
+                .collect()
+        };
+        diff.reordered = kept(&self.producers, &new.producers) != kept(&new.producers, &self.producers);
+        diff
+    }
+}
+
+/// The producers that differ between two schedules.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScheduleDiff {
+    /// Producers of the new schedule only
+    pub added: Vec<ProducerAuthority>,
+    /// Producers of the old schedule only
+    pub removed: Vec<ProducerAuthority>,
+    /// Producers of both schedules whose authority changed, the old one then the new one
+    pub key_changed: Vec<(ProducerAuthority, ProducerAuthority)>,
+    /// Producers of both schedules are in a different order, which moves their rounds
+    pub reordered: bool,
+}
+
+impl ScheduleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.key_changed.is_empty() && !self.reordered
+    }
+}
+
+impl BlockSigningAuthority {
+    /// Check the signatures of a block, `producer_signature` followed by the ones of the
+    /// `additional_block_signatures` extension, against the authority like nodeos does:
+    /// every signature must be recovered to a different key of the authority and the
+    /// weights of those keys must reach the threshold.
+    pub fn verify_signatures(&self, digest: &Checksum256, signatures: &[Signature]) -> crate::Result<()> {
+        let authority = &self.1;
+        if signatures.len() > authority.keys.len() {
+            return Err(BlockHeaderStateError::TooManySignatures.into());
+        }
+
+        let mut signed = vec![false; authority.keys.len()];
+        let mut weight = 0u32;
+        for (i, signature) in signatures.iter().enumerate() {
+            if signatures[..i].iter().any(|other| other.data[..] == signature.data[..]) {
+                return Err(BlockHeaderStateError::DuplicateSignature.into());
+            }
+            let key = PublicKey::recover(digest.as_bytes(), signature)
+                .map_err(|_| BlockHeaderStateError::InvalidSignature)?;
+            let index = authority.keys.iter()
+                .position(|kw| kw.key.data[..] == key.data[..])
+                .ok_or(BlockHeaderStateError::UnknownSigningKey)?;
+            if signed[index] {
+                return Err(BlockHeaderStateError::DuplicateSigningKey.into());
+            }
+            signed[index] = true;
+            weight += u32::from(authority.keys[index].weight);
+        }
+
+        if weight == 0 || weight < authority.threshold {
+            return Err(BlockHeaderStateError::InvalidSignature.into());
+        }
+        Ok(())
+    }
+}
+
+impl From<ProducerKey> for ProducerAuthority {
+    fn from(producer: ProducerKey) -> Self {
+        let authority_v0 = BlockSigningAuthorityV0 {
+            threshold: 1,
+            keys: vec![KeyWeight { key: producer.block_signing_key, weight: 1 }],
+        };
+        ProducerAuthority {
+            producer_name: producer.producer_name,
+            authority: BlockSigningAuthority(UnsignedInt::from(0u32), authority_v0),
+        }
+    }
+}
+
+/// A legacy schedule as the single key authorities nodeos converts it to.
+impl From<ProducerSchedule> for ProducerAuthoritySchedule {
+    fn from(schedule: ProducerSchedule) -> Self {
+        ProducerAuthoritySchedule::new(
+            schedule.version,
+            schedule.producers.into_iter().map(ProducerAuthority::from).collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -252,5 +375,120 @@ mod test {
 
         let producer = AccountName::from_str("tokenika4tst").unwrap();
         assert_eq!(new_producers.get_producer_key(producer).to_string(), "EOS6wkp1PpqQUgEA6UtgW21Zo3o1XcQeLXzcLLgKcPJhTz2aSF6fz");
+        assert_eq!(new_producers.get_producer_authority(producer).unwrap().1.keys[0].key.to_string(), "EOS6wkp1PpqQUgEA6UtgW21Zo3o1XcQeLXzcLLgKcPJhTz2aSF6fz");
+        assert!(new_producers.get_producer_authority(AccountName::from_str("eosio").unwrap()).is_none());
+    }
+
+    fn assert_verify_error(result: crate::Result<()>, expected: BlockHeaderStateError) {
+        match result {
+            Err(crate::Error::BlockHeaderStateError(e)) => assert_eq!(e, expected),
+            _ => panic!("expect {:?}", expected),
+        }
+    }
+
+    #[test]
+    fn verify_wtmsig_signatures_should_work() {
+        let sks = crate::test_utils::secret_keys();
+        // the last key is not part of the authority
+        let key_weights = sks[..3].iter().zip([1u16, 1, 2].iter())
+            .map(|(sk, weight)| KeyWeight { key: keys::public::PublicKey::from(sk).into(), weight: *weight })
+            .collect();
+        let authority = BlockSigningAuthority(UnsignedInt::from(0u32), BlockSigningAuthorityV0 { threshold: 2, keys: key_weights });
+
+        let digest = Checksum256::hash_from_slice(b"block");
+        let sigs: Vec<Signature> = sks.iter().map(|sk| sk.sign_hash(digest.as_bytes()).unwrap().into()).collect();
+
+        assert!(authority.verify_signatures(&digest, &[sigs[0].clone(), sigs[1].clone()]).is_ok());
+        assert!(authority.verify_signatures(&digest, &[sigs[1].clone(), sigs[0].clone()]).is_ok());
+        assert!(authority.verify_signatures(&digest, &sigs[2..3]).is_ok());
+
+        assert_verify_error(authority.verify_signatures(&digest, &sigs[..1]), BlockHeaderStateError::InvalidSignature);
+        assert_verify_error(authority.verify_signatures(&digest, &[]), BlockHeaderStateError::InvalidSignature);
+        assert_verify_error(
+            authority.verify_signatures(&digest, &[sigs[0].clone(), sigs[0].clone()]),
+            BlockHeaderStateError::DuplicateSignature,
+        );
+        assert_verify_error(
+            authority.verify_signatures(&digest, &[sigs[2].clone(), sigs[3].clone()]),
+            BlockHeaderStateError::UnknownSigningKey,
+        );
+        assert_verify_error(authority.verify_signatures(&digest, &sigs), BlockHeaderStateError::TooManySignatures);
+
+        // signatures on another digest recover to keys outside the authority
+        let other = Checksum256::hash_from_slice(b"other block");
+        assert_verify_error(authority.verify_signatures(&other, &sigs[..2]), BlockHeaderStateError::UnknownSigningKey);
+    }
+
+    #[test]
+    fn scheduled_producer_should_work() {
+        let producers: Vec<ProducerKey> = ["eosio", "proda", "prodb"].iter().map(|name| ProducerKey {
+            producer_name: AccountName::from_str(name).unwrap(),
+            block_signing_key: Default::default(),
+        }).collect();
+        let schedule = ProducerSchedule::new(1, producers);
+        let v2 = ProducerAuthoritySchedule::from(schedule.clone());
+        let proda = AccountName::from_str("proda").unwrap();
+
+        for slot in 1_000..1_036 {
+            let timestamp = BlockTimestamp(slot);
+            let producer = schedule.get_scheduled_producer(timestamp).unwrap().producer_name;
+            assert_eq!(v2.get_scheduled_producer(timestamp).unwrap().producer_name, producer);
+            assert_eq!(producer == proda, (1_020..1_032).contains(&slot));
+        }
+        assert_eq!(schedule.next_round_start_of(proda, BlockTimestamp(1_000)), Some(BlockTimestamp(1_020)));
+        assert_eq!(v2.next_round_start_of(proda, BlockTimestamp(1_021)), Some(BlockTimestamp(1_056)));
+        assert_eq!(v2.next_round_start_of(AccountName::from_str("prodc").unwrap(), BlockTimestamp(1_000)), None);
+        assert!(ProducerAuthoritySchedule::new(0, vec![]).get_scheduled_producer(BlockTimestamp(1_000)).is_none());
+    }
+
+    #[test]
+    fn schedule_diff_should_work() {
+        let producer = |name: &str, wif: &str| -> ProducerAuthority {
+            ProducerKey {
+                producer_name: AccountName::from_str(name).unwrap(),
+                block_signing_key: crate::test_utils::public_key(wif),
+            }.into()
+        };
+        let wif = crate::test_utils::WIFS[0];
+        let other_wif = crate::test_utils::WIFS[1];
+        let old = ProducerAuthoritySchedule::new(1, vec![
+            producer("proda", wif),
+            producer("prodb", wif),
+            producer("prodc", wif),
+        ]);
+        let new = ProducerAuthoritySchedule::new(2, vec![
+            producer("prodd", wif),
+            producer("prodc", wif),
+            producer("proda", other_wif),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![producer("prodd", wif)]);
+        assert_eq!(diff.removed, vec![producer("prodb", wif)]);
+        assert_eq!(diff.key_changed, vec![(producer("proda", wif), producer("proda", other_wif))]);
+        assert!(!diff.is_empty());
+
+        let reversed = new.diff(&old);
+        assert_eq!(reversed.added, diff.removed);
+        assert_eq!(reversed.removed, diff.added);
+
+        // prodc moves before proda
+        assert!(diff.reordered);
+        assert!(reversed.reordered);
+
+        // only the order differs
+        let reordered = ProducerAuthoritySchedule::new(2, old.producers.iter().rev().cloned().collect());
+        let diff = old.diff(&reordered);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.key_changed.is_empty());
+        assert!(diff.reordered);
+        assert!(!diff.is_empty());
+
+        // a producer joining at the end keeps the order of the others
+        let mut extended = old.clone();
+        extended.producers.push(producer("prodd", wif));
+        let diff = old.diff(&extended);
+        assert!(!diff.reordered);
+        assert_eq!(diff.added, vec![producer("prodd", wif)]);
+        assert!(old.diff(&old).is_empty());
     }
 }
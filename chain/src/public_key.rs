@@ -2,7 +2,7 @@
 use alloc::string::ToString;
 use crate::{NumBytes, Read, UnsignedInt, Write, Signature};
 use core::{
-    convert::{TryFrom, TryInto},
+    convert::TryFrom,
     fmt, marker::PhantomData,
     str::FromStr
 };
@@ -67,9 +67,16 @@ impl PublicKey {
     }
 
     pub fn verify(&self, hash: &[u8], signature: &Signature) -> crate::Result<()> {
-        let keys = keys::public::PublicKey::try_from(self.clone())?;
-        let sig: &keys::signature::Signature = &signature.clone().try_into()?;
-        keys.verify_hash(hash, sig).map_err(crate::Error::VerificationError)
+        let keys = keys::public::PublicKey::from_slice(&self.data).map_err(crate::Error::Keys)?;
+        let sig = keys::signature::Signature::from_compact(&signature.data).map_err(crate::Error::Keys)?;
+        keys.verify_hash(hash, &sig).map_err(crate::Error::VerificationError)
+    }
+
+    /// The key that produced `signature` on `hash`.
+    pub fn recover(hash: &[u8], signature: &Signature) -> crate::Result<Self> {
+        let sig = keys::signature::Signature::from_compact(&signature.data).map_err(crate::Error::Keys)?;
+        let keys = keys::public::PublicKey::recover_hash(hash, &sig).map_err(crate::Error::Keys)?;
+        Ok(keys.into())
     }
 }
 
@@ -139,6 +146,21 @@ impl PartialEq for PublicKey {
     }
 }
 
+impl Eq for PublicKey {}
+
+/// Keys are ordered by type, then by their compressed bytes, as nodeos sorts them in an authority.
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKey {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.type_.cmp(&other.type_).then_with(|| self.data.cmp(&other.data))
+    }
+}
+
 impl core::fmt::Debug for PublicKey {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         core::fmt::Debug::fmt(&self.type_, f)?;
@@ -0,0 +1,1172 @@
+//! Types of the state history plugin (SHiP) websocket protocol and their binary codec.
+//! <https://github.com/EOSIO/eos/blob/v2.0.9/plugins/state_history_plugin/state_history_plugin_abi.cpp>
+//!
+//! After the ABI, sent as text, every message is a packed `ShipRequest` or `ShipResult`.
+//! The block, traces and deltas of a `GetBlocksResultV0` are packed in opaque bytes and
+//! decoded on demand. Variants are packed as their index followed by the value.
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{
+    AccountName, Action, ActionName, ActionReceipt, Authority, BlockchainParameters, BlockTimestamp,
+    Checksum256, Extension, NumBytes, PermissionName, ProducerAuthoritySchedule, ProducerSchedule,
+    Read, ReadError, ScopeName, Signature, SignedBlock, TableName, TimePoint, TimePointSec,
+    UnsignedInt, Write, WriteError
+};
+
+macro_rules! ship_variant {
+    (
+        $(#[$meta:meta])*
+        $name:ident { $first_index:literal => $first:ident($first_ty:ty) $(, $index:literal => $variant:ident($ty:ty))* $(,)? }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum $name {
+            $first($first_ty),
+            $($variant($ty),)*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name::$first(Default::default())
+            }
+        }
+
+        impl NumBytes for $name {
+            fn num_bytes(&self) -> usize {
+                match self {
+                    $name::$first(value) => UnsignedInt::from($first_index as u32).num_bytes() + value.num_bytes(),
+                    $($name::$variant(value) => UnsignedInt::from($index as u32).num_bytes() + value.num_bytes(),)*
+                }
+            }
+        }
+
+        impl Read for $name {
+            fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+                match usize::from(UnsignedInt::read(bytes, pos)?) {
+                    $first_index => Ok($name::$first(<$first_ty>::read(bytes, pos)?)),
+                    $($index => Ok($name::$variant(<$ty>::read(bytes, pos)?)),)*
+                    _ => Err(ReadError::NotSupportMessageType),
+                }
+            }
+        }
+
+        impl Write for $name {
+            fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+                match self {
+                    $name::$first(value) => {
+                        UnsignedInt::from($first_index as u32).write(bytes, pos)?;
+                        value.write(bytes, pos)
+                    }
+                    $($name::$variant(value) => {
+                        UnsignedInt::from($index as u32).write(bytes, pos)?;
+                        value.write(bytes, pos)
+                    })*
+                }
+            }
+        }
+    };
+}
+
+/// Decode opaque bytes of a message, `None` when they were not requested.
+fn decode<T: Read>(data: &Option<Vec<u8>>) -> crate::Result<Option<T>> {
+    match data {
+        Some(data) => T::read(data, &mut 0).map(Some).map_err(crate::Error::BytesReadError),
+        None => Ok(None),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct BlockPosition {
+    pub block_num: u32,
+    pub block_id: Checksum256,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetStatusRequestV0;
+
+impl NumBytes for GetStatusRequestV0 {
+    fn num_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl Read for GetStatusRequestV0 {
+    fn read(_bytes: &[u8], _pos: &mut usize) -> Result<Self, ReadError> {
+        Ok(GetStatusRequestV0)
+    }
+}
+
+impl Write for GetStatusRequestV0 {
+    fn write(&self, _bytes: &mut [u8], _pos: &mut usize) -> Result<(), WriteError> {
+        Ok(())
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GetBlocksRequestV0 {
+    pub start_block_num: u32,
+    /// Exclusive, `u32::MAX` to follow the chain
+    pub end_block_num: u32,
+    pub max_messages_in_flight: u32,
+    /// Blocks the client already has, the plugin restarts from the first fork it sees
+    pub have_positions: Vec<BlockPosition>,
+    pub irreversible_only: bool,
+    pub fetch_block: bool,
+    pub fetch_traces: bool,
+    pub fetch_deltas: bool,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GetBlocksAckRequestV0 {
+    pub num_messages: u32,
+}
+
+ship_variant! {
+    /// A message sent to the plugin.
+    ShipRequest {
+        0 => GetStatusRequestV0(GetStatusRequestV0),
+        1 => GetBlocksRequestV0(GetBlocksRequestV0),
+        2 => GetBlocksAckRequestV0(GetBlocksAckRequestV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GetStatusResultV0 {
+    pub head: BlockPosition,
+    pub last_irreversible: BlockPosition,
+    pub trace_begin_block: u32,
+    pub trace_end_block: u32,
+    pub chain_state_begin_block: u32,
+    pub chain_state_end_block: u32,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GetBlocksResultV0 {
+    pub head: BlockPosition,
+    pub last_irreversible: BlockPosition,
+    pub this_block: Option<BlockPosition>,
+    pub prev_block: Option<BlockPosition>,
+    /// A packed `SignedBlock`
+    pub block: Option<Vec<u8>>,
+    /// Packed `TransactionTrace`s
+    pub traces: Option<Vec<u8>>,
+    /// Packed `TableDelta`s
+    pub deltas: Option<Vec<u8>>,
+}
+
+impl GetBlocksResultV0 {
+    pub fn signed_block(&self) -> crate::Result<Option<SignedBlock>> {
+        decode(&self.block)
+    }
+
+    pub fn transaction_traces(&self) -> crate::Result<Option<Vec<TransactionTrace>>> {
+        decode(&self.traces)
+    }
+
+    pub fn table_deltas(&self) -> crate::Result<Option<Vec<TableDelta>>> {
+        decode(&self.deltas)
+    }
+}
+
+ship_variant! {
+    /// A message sent by the plugin.
+    ShipResult {
+        0 => GetStatusResultV0(GetStatusResultV0),
+        1 => GetBlocksResultV0(GetBlocksResultV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct AccountDelta {
+    pub account: AccountName,
+    pub delta: i64,
+}
+
+ship_variant! {
+    ShipActionReceipt {
+        0 => V0(ActionReceipt),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ActionTraceV0 {
+    pub action_ordinal: UnsignedInt,
+    /// 0 for the actions of the transaction
+    pub creator_action_ordinal: UnsignedInt,
+    /// `None` when the action failed
+    pub receipt: Option<ShipActionReceipt>,
+    pub receiver: AccountName,
+    pub act: Action,
+    pub context_free: bool,
+    pub elapsed: i64,
+    pub console: String,
+    pub account_ram_deltas: Vec<AccountDelta>,
+    pub except: Option<String>,
+    pub error_code: Option<u64>,
+}
+
+/// `ActionTraceV0` with the value returned by the action, from EOSIO 2.1.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ActionTraceV1 {
+    pub action_ordinal: UnsignedInt,
+    pub creator_action_ordinal: UnsignedInt,
+    pub receipt: Option<ShipActionReceipt>,
+    pub receiver: AccountName,
+    pub act: Action,
+    pub context_free: bool,
+    pub elapsed: i64,
+    pub console: String,
+    pub account_ram_deltas: Vec<AccountDelta>,
+    pub except: Option<String>,
+    pub error_code: Option<u64>,
+    pub return_value: Vec<u8>,
+}
+
+ship_variant! {
+    ActionTrace {
+        0 => V0(ActionTraceV0),
+        1 => V1(ActionTraceV1),
+    }
+}
+
+impl ActionTrace {
+    pub fn receiver(&self) -> AccountName {
+        match self {
+            ActionTrace::V0(trace) => trace.receiver,
+            ActionTrace::V1(trace) => trace.receiver,
+        }
+    }
+
+    pub fn act(&self) -> &Action {
+        match self {
+            ActionTrace::V0(trace) => &trace.act,
+            ActionTrace::V1(trace) => &trace.act,
+        }
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct PartialTransactionV0 {
+    pub expiration: TimePointSec,
+    pub ref_block_num: u16,
+    pub ref_block_prefix: u32,
+    pub max_net_usage_words: UnsignedInt,
+    pub max_cpu_usage_ms: u8,
+    pub delay_sec: UnsignedInt,
+    pub transaction_extensions: Vec<Extension>,
+    pub signatures: Vec<Signature>,
+    pub context_free_data: Vec<Vec<u8>>,
+}
+
+ship_variant! {
+    PartialTransaction {
+        0 => V0(PartialTransactionV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct TransactionTraceV0 {
+    pub id: Checksum256,
+    /// `executed`, `soft_fail`, `hard_fail`, `delayed` or `expired` in this order
+    pub status: u8,
+    pub cpu_usage_us: u32,
+    pub net_usage_words: UnsignedInt,
+    pub elapsed: i64,
+    pub net_usage: u64,
+    pub scheduled: bool,
+    pub action_traces: Vec<ActionTrace>,
+    pub account_delta: Option<AccountDelta>,
+    pub except: Option<String>,
+    pub error_code: Option<u64>,
+    pub failed_dtrx_trace: Option<Box<TransactionTrace>>,
+    pub partial: Option<PartialTransaction>,
+}
+
+ship_variant! {
+    TransactionTrace {
+        0 => V0(TransactionTraceV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct Row {
+    /// `false` when the row was removed
+    pub present: bool,
+    /// The packed row, e.g. a `ShipContractRow` for the `contract_row` table
+    pub data: Vec<u8>,
+}
+
+impl Row {
+    pub fn decode<T: Read>(&self) -> crate::Result<T> {
+        T::read(&self.data, &mut 0).map_err(crate::Error::BytesReadError)
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct TableDeltaV0 {
+    /// The state table, e.g. `account`, `permission` or `contract_row`
+    pub name: String,
+    pub rows: Vec<Row>,
+}
+
+ship_variant! {
+    TableDelta {
+        0 => V0(TableDeltaV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractTableV0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub payer: AccountName,
+}
+
+ship_variant! {
+    ShipContractTable {
+        0 => V0(ContractTableV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractRowV0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub value: Vec<u8>,
+}
+
+ship_variant! {
+    ShipContractRow {
+        0 => V0(ContractRowV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractIndex64V0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: u64,
+}
+
+ship_variant! {
+    ShipContractIndex64 {
+        0 => V0(ContractIndex64V0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractIndex128V0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: u128,
+}
+
+ship_variant! {
+    ShipContractIndex128 {
+        0 => V0(ContractIndex128V0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractIndex256V0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: Checksum256,
+}
+
+ship_variant! {
+    ShipContractIndex256 {
+        0 => V0(ContractIndex256V0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractIndexDoubleV0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: f64,
+}
+
+ship_variant! {
+    ShipContractIndexDouble {
+        0 => V0(ContractIndexDoubleV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ContractIndexLongDoubleV0 {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub primary_key: u64,
+    pub payer: AccountName,
+    /// An IEEE 754 quadruple precision float, little endian
+    pub secondary_key: [u8; 16],
+}
+
+ship_variant! {
+    ShipContractIndexLongDouble {
+        0 => V0(ContractIndexLongDoubleV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct AccountV0 {
+    pub name: AccountName,
+    pub creation_date: BlockTimestamp,
+    pub abi: Vec<u8>,
+}
+
+ship_variant! {
+    ShipAccount {
+        0 => V0(AccountV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct PermissionV0 {
+    pub owner: AccountName,
+    pub name: PermissionName,
+    pub parent: PermissionName,
+    pub last_updated: TimePoint,
+    pub auth: Authority,
+}
+
+ship_variant! {
+    ShipPermission {
+        0 => V0(PermissionV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct PermissionLinkV0 {
+    pub account: AccountName,
+    pub code: AccountName,
+    /// Empty for all the actions of `code`
+    pub message_type: ActionName,
+    pub required_permission: PermissionName,
+}
+
+ship_variant! {
+    ShipPermissionLink {
+        0 => V0(PermissionLinkV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct CodeId {
+    pub vm_type: u8,
+    pub vm_version: u8,
+    pub code_hash: Checksum256,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct AccountMetadataV0 {
+    pub name: AccountName,
+    pub privileged: bool,
+    pub last_code_update: TimePoint,
+    /// `None` when the account has no contract
+    pub code: Option<CodeId>,
+}
+
+ship_variant! {
+    ShipAccountMetadata {
+        0 => V0(AccountMetadataV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct CodeV0 {
+    pub vm_type: u8,
+    pub vm_version: u8,
+    pub code_hash: Checksum256,
+    pub code: Vec<u8>,
+}
+
+ship_variant! {
+    ShipCode {
+        0 => V0(CodeV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ResourceLimitsV0 {
+    pub owner: AccountName,
+    /// -1 when unlimited
+    pub net_weight: i64,
+    pub cpu_weight: i64,
+    pub ram_bytes: i64,
+}
+
+ship_variant! {
+    ShipResourceLimits {
+        0 => V0(ResourceLimitsV0),
+    }
+}
+
+/// An exponential moving average of a usage.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct UsageAccumulatorV0 {
+    /// The block the average was last updated in
+    pub last_ordinal: u32,
+    /// The average scaled by 1_000_000
+    pub value_ex: u64,
+    pub consumed: u64,
+}
+
+ship_variant! {
+    ShipUsageAccumulator {
+        0 => V0(UsageAccumulatorV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ResourceUsageV0 {
+    pub owner: AccountName,
+    pub net_usage: ShipUsageAccumulator,
+    pub cpu_usage: ShipUsageAccumulator,
+    pub ram_usage: u64,
+}
+
+ship_variant! {
+    ShipResourceUsage {
+        0 => V0(ResourceUsageV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ResourceLimitsStateV0 {
+    pub average_block_net_usage: ShipUsageAccumulator,
+    pub average_block_cpu_usage: ShipUsageAccumulator,
+    pub total_net_weight: u64,
+    pub total_cpu_weight: u64,
+    pub total_ram_bytes: u64,
+    pub virtual_net_limit: u64,
+    pub virtual_cpu_limit: u64,
+}
+
+ship_variant! {
+    ShipResourceLimitsState {
+        0 => V0(ResourceLimitsStateV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ResourceLimitsRatioV0 {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+ship_variant! {
+    ShipResourceLimitsRatio {
+        0 => V0(ResourceLimitsRatioV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ElasticLimitParametersV0 {
+    pub target: u64,
+    pub max: u64,
+    pub periods: u32,
+    pub max_multiplier: u32,
+    pub contract_rate: ShipResourceLimitsRatio,
+    pub expand_rate: ShipResourceLimitsRatio,
+}
+
+ship_variant! {
+    ShipElasticLimitParameters {
+        0 => V0(ElasticLimitParametersV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ResourceLimitsConfigV0 {
+    pub cpu_limit_parameters: ShipElasticLimitParameters,
+    pub net_limit_parameters: ShipElasticLimitParameters,
+    pub account_cpu_usage_average_window: u32,
+    pub account_net_usage_average_window: u32,
+}
+
+ship_variant! {
+    ShipResourceLimitsConfig {
+        0 => V0(ResourceLimitsConfigV0),
+    }
+}
+
+ship_variant! {
+    ShipChainConfig {
+        0 => V0(BlockchainParameters),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GlobalPropertyV0 {
+    /// The block proposing `proposed_schedule`, `None` when no schedule is proposed
+    pub proposed_schedule_block_num: Option<u32>,
+    pub proposed_schedule: ProducerSchedule,
+    pub configuration: ShipChainConfig,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GlobalPropertyV1 {
+    pub proposed_schedule_block_num: Option<u32>,
+    pub proposed_schedule: ProducerAuthoritySchedule,
+    pub configuration: ShipChainConfig,
+    pub chain_id: Checksum256,
+}
+
+ship_variant! {
+    ShipGlobalProperty {
+        0 => V0(GlobalPropertyV0),
+        1 => V1(GlobalPropertyV1),
+    }
+}
+
+/// A deferred transaction waiting for its delay.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct GeneratedTransactionV0 {
+    pub sender: AccountName,
+    pub sender_id: u128,
+    pub payer: AccountName,
+    pub trx_id: Checksum256,
+    pub packed_trx: Vec<u8>,
+}
+
+ship_variant! {
+    ShipGeneratedTransaction {
+        0 => V0(GeneratedTransactionV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ActivatedProtocolFeatureV0 {
+    pub feature_digest: Checksum256,
+    pub activation_block_num: u32,
+}
+
+ship_variant! {
+    ShipActivatedProtocolFeature {
+        0 => V0(ActivatedProtocolFeatureV0),
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ProtocolStateV0 {
+    pub activated_protocol_features: Vec<ShipActivatedProtocolFeature>,
+}
+
+ship_variant! {
+    ShipProtocolState {
+        0 => V0(ProtocolStateV0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::str::FromStr;
+    use crate::{
+        Asset, PermissionLevel, SerializeData, SignedBlockHeader, TrxKinds, utils::flat_map::FlatMap
+    };
+
+    fn pack<T: Write + NumBytes>(value: &T) -> Vec<u8> {
+        let mut data = vec![0u8; value.num_bytes()];
+        value.write(&mut data, &mut 0).unwrap();
+        data
+    }
+
+    fn unpack<T: Read>(data: &[u8]) -> T {
+        let pos = &mut 0;
+        let value = T::read(data, pos).unwrap();
+        assert_eq!(*pos, data.len());
+        value
+    }
+
+    #[test]
+    fn ship_requests_should_work() {
+        let status = ShipRequest::GetStatusRequestV0(GetStatusRequestV0);
+        assert_eq!(hex::encode(pack(&status)), "00");
+
+        let blocks = ShipRequest::GetBlocksRequestV0(GetBlocksRequestV0 {
+            start_block_num: 1,
+            end_block_num: u32::MAX,
+            max_messages_in_flight: 1,
+            have_positions: vec![],
+            irreversible_only: false,
+            fetch_block: true,
+            fetch_traces: true,
+            fetch_deltas: true,
+        });
+        let data = pack(&blocks);
+        assert_eq!(hex::encode(&data), "0101000000ffffffff010000000000010101");
+        assert_eq!(unpack::<ShipRequest>(&data), blocks);
+
+        let ack = ShipRequest::GetBlocksAckRequestV0(GetBlocksAckRequestV0 { num_messages: 5 });
+        assert_eq!(hex::encode(pack(&ack)), "0205000000");
+        assert_eq!(unpack::<ShipRequest>(&hex::decode("00").unwrap()), status);
+        assert!(ShipRequest::read(&[3], &mut 0).is_err());
+    }
+
+    #[test]
+    fn ship_status_result_should_be_decoded() {
+        let head_id = "00077cb769fc3f025a500ef3478ef9e65e401d05f9b9a7272c7ae3c3d0ea2b9e";
+        let lib_id = "00077cb6d5534a23579751f578148b8f0f2da54cd22243b4d6c17ba398ab8a90";
+        let fixture = format!(
+            "00b77c0700{}b67c0700{}01000000b87c070001000000b87c0700",
+            head_id, lib_id,
+        );
+        match unpack::<ShipResult>(&hex::decode(&fixture).unwrap()) {
+            ShipResult::GetStatusResultV0(status) => {
+                assert_eq!(status.head.block_num, 490_679);
+                assert_eq!(status.head.block_id, Checksum256::from_str(head_id).unwrap());
+                assert_eq!(status.last_irreversible.block_num, 490_678);
+                assert_eq!(status.last_irreversible.block_id, Checksum256::from_str(lib_id).unwrap());
+                assert_eq!((status.trace_begin_block, status.trace_end_block), (1, 490_680));
+                assert_eq!((status.chain_state_begin_block, status.chain_state_end_block), (1, 490_680));
+            }
+            result => panic!("expect a status, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn ship_table_deltas_should_be_decoded() {
+        // the deltas of a block where alice holds 10.0000 EOS
+        let fixture = "01000c636f6e74726163745f726f7701013a0000a6823403ea30550000000000855c34000000384f4d1132\
+            454f5300000000000000000000855c3410a08601000000000004454f5300000000";
+        let deltas: Vec<TableDelta> = unpack(&hex::decode(fixture).unwrap());
+        let TableDelta::V0(delta) = &deltas[0];
+        assert_eq!(delta.name, "contract_row");
+        assert!(delta.rows[0].present);
+
+        let ShipContractRow::V0(row) = delta.rows[0].decode::<ShipContractRow>().unwrap();
+        assert_eq!(row.code, AccountName::from_str("eosio.token").unwrap());
+        assert_eq!(row.scope, ScopeName::from_str("alice").unwrap());
+        assert_eq!(row.table, TableName::from_str("accounts").unwrap());
+        assert_eq!(row.primary_key, 0x534f45);
+        assert_eq!(hex::encode(&row.value), "a08601000000000004454f5300000000");
+    }
+
+    #[test]
+    fn ship_state_rows_should_be_decoded() {
+        let alice = AccountName::from_str("alice").unwrap();
+        // the secondary keys of the row 1 of alice pairs
+        let index = "000000000000855c340000000000855c3400000000007c9da901000000000000000000000000855c34";
+
+        let ShipContractIndex128::V0(row) = unpack(&hex::decode(format!("{}05000000000000000100000000000000", index)).unwrap());
+        assert_eq!((row.code, row.primary_key, row.payer), (alice, 1, alice));
+        assert_eq!(row.table, TableName::from_str("pairs").unwrap());
+        assert_eq!(row.secondary_key, (1 << 64) | 5);
+
+        let ShipContractIndexDouble::V0(row) = unpack(&hex::decode(format!("{}000000000000e0bf", index)).unwrap());
+        assert_eq!(row.secondary_key, -0.5);
+
+        // 1.0 as a quadruple precision float
+        let one = "0000000000000000000000000000ff3f";
+        let ShipContractIndexLongDouble::V0(row) = unpack(&hex::decode(format!("{}{}", index, one)).unwrap());
+        assert_eq!(hex::encode(row.secondary_key), one);
+
+        let code_hash = Checksum256::hash_from_slice(b"eosio.token");
+        let ShipContractIndex256::V0(row) = unpack(&hex::decode(format!("{}{}", index, code_hash)).unwrap());
+        assert_eq!(row.secondary_key, code_hash);
+
+        // eosio.token last set its code at the mainnet genesis
+        let fixture = format!("0000a6823403ea305500c0b680e61c6e0500010000{}", code_hash);
+        let ShipAccountMetadata::V0(metadata) = unpack(&hex::decode(fixture).unwrap());
+        assert_eq!(metadata.name, AccountName::from_str("eosio.token").unwrap());
+        assert!(!metadata.privileged);
+        assert_eq!(metadata.last_code_update, TimePoint::from(1_528_445_288_888_000));
+        assert_eq!(metadata.code, Some(CodeId { vm_type: 0, vm_version: 0, code_hash }));
+
+        let fixture = "000000000000855c34ffffffffffffffffffffffffffffffffb40b000000000000";
+        let ShipResourceLimits::V0(limits) = unpack(&hex::decode(fixture).unwrap());
+        assert_eq!((limits.owner, limits.net_weight, limits.cpu_weight, limits.ram_bytes), (alice, -1, -1, 2996));
+
+        // the default config of nodeos
+        let fixture = "00\
+            00204e000000000000400d03000000000078000000e803000000630000000000000064000000000000\
+            0000e803000000000000e703000000000000\
+            009999010000000000000010000000000078000000e803000000630000000000000064000000000000\
+            0000e803000000000000e703000000000000\
+            00a3020000a30200";
+        let ShipResourceLimitsConfig::V0(config) = unpack(&hex::decode(fixture).unwrap());
+        let ShipElasticLimitParameters::V0(cpu) = &config.cpu_limit_parameters;
+        assert_eq!((cpu.target, cpu.max, cpu.periods, cpu.max_multiplier), (20_000, 200_000, 120, 1000));
+        let ShipResourceLimitsRatio::V0(contract_rate) = &cpu.contract_rate;
+        assert_eq!((contract_rate.numerator, contract_rate.denominator), (99, 100));
+        let ShipResourceLimitsRatio::V0(expand_rate) = &cpu.expand_rate;
+        assert_eq!((expand_rate.numerator, expand_rate.denominator), (1000, 999));
+        let ShipElasticLimitParameters::V0(net) = &config.net_limit_parameters;
+        assert_eq!((net.target, net.max), (104_857, 1_048_576));
+        assert_eq!(config.account_cpu_usage_average_window, 172_800);
+        assert_eq!(config.account_net_usage_average_window, 172_800);
+
+        // alice requires her active permission for eosio.token transfers
+        let fixture = "000000000000855c3400a6823403ea3055000000572d3ccdcd00000000a8ed3232";
+        let ShipPermissionLink::V0(link) = unpack(&hex::decode(fixture).unwrap());
+        assert_eq!(link.account, alice);
+        assert_eq!(link.code, AccountName::from_str("eosio.token").unwrap());
+        assert_eq!(link.message_type, ActionName::from_str("transfer").unwrap());
+        assert_eq!(link.required_permission, PermissionName::from_str("active").unwrap());
+
+        // chain_config_v0 with the values the mainnet started with
+        let chain_config = "00\
+            0000100000000000e803000000000800\
+            0c000000f40100001400000064000000\
+            400d0300e8030000f049020064000000\
+            100e00005802000080533b0000100000\
+            04000600";
+        let eosio_key = "0002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf";
+        // version 1 proposed in block 1000, producing with the eosio key
+        let fixture = format!("00\
+            01e8030000\
+            01000000010000000000ea3055{}\
+            {}", eosio_key, chain_config);
+        let global = unpack::<ShipGlobalProperty>(&hex::decode(fixture).unwrap());
+        let global = match global {
+            ShipGlobalProperty::V0(global) => global,
+            global => panic!("expect a v0 global property, got {:?}", global),
+        };
+        assert_eq!(global.proposed_schedule_block_num, Some(1_000));
+        assert_eq!(global.proposed_schedule.version, 1);
+        assert_eq!(global.proposed_schedule.producers[0].producer_name, AccountName::from_str("eosio").unwrap());
+        assert_eq!(
+            global.proposed_schedule.producers[0].block_signing_key.to_string(),
+            "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV",
+        );
+        let ShipChainConfig::V0(parameters) = &global.configuration;
+        assert_eq!(parameters.max_block_net_usage, 1_048_576);
+        assert_eq!(parameters.max_block_cpu_usage, 200_000);
+        assert_eq!(parameters.max_transaction_delay, 3_888_000);
+        assert_eq!((parameters.max_inline_action_depth, parameters.max_authority_depth), (4, 6));
+
+        // no proposed schedule, an empty authority schedule and the mainnet chain id
+        let chain_id = "aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906";
+        let fixture = format!("01000000000000{}{}", chain_config, chain_id);
+        let global = unpack::<ShipGlobalProperty>(&hex::decode(&fixture).unwrap());
+        let global = match global {
+            ShipGlobalProperty::V1(global) => global,
+            global => panic!("expect a v1 global property, got {:?}", global),
+        };
+        assert_eq!(global.proposed_schedule_block_num, None);
+        assert!(global.proposed_schedule.producers.is_empty());
+        assert_eq!(global.configuration, ShipChainConfig::V0(parameters.clone()));
+        assert_eq!(global.chain_id, Checksum256::from_str(chain_id).unwrap());
+        assert_eq!(hex::encode(pack(&ShipGlobalProperty::V1(global))), fixture);
+
+        let trx_id = "69569070707daf4b26cf07b657fa5b0b685ec503d33e96618a267e5f0eb86956";
+        // sent by alice with sender_id 1 and paid by her, the packed transaction is 3 bytes
+        let fixture = format!(
+            "000000000000855c34010000000000000000000000000000000000000000855c34{}03010203",
+            trx_id,
+        );
+        let ShipGeneratedTransaction::V0(generated) = unpack(&hex::decode(fixture).unwrap());
+        assert_eq!((generated.sender, generated.sender_id, generated.payer), (alice, 1, alice));
+        assert_eq!(generated.trx_id, Checksum256::from_str(trx_id).unwrap());
+        assert_eq!(generated.packed_trx, vec![1, 2, 3]);
+
+        let feature = "0ec7e080177b2c02b278d5088611686b49d739925a92d9bfcacd7fc6b74053bd";
+        // PREACTIVATE_FEATURE activated in block 2
+        let fixture = format!("000100{}02000000", feature);
+        let ShipProtocolState::V0(protocol) = unpack(&hex::decode(fixture).unwrap());
+        let ShipActivatedProtocolFeature::V0(activated) = &protocol.activated_protocol_features[0];
+        assert_eq!(activated.feature_digest, Checksum256::from_str(feature).unwrap());
+        assert_eq!(activated.activation_block_num, 2);
+    }
+
+    /// A `get_blocks_result_v0` laid out by hand field by field after the nodeos 2.0 ABI, with
+    /// the traces of a transfer and the deltas it causes. It is not a capture from a running
+    /// nodeos, like the other fixtures of this module, so it can't catch a misreading of the
+    /// ABI that went into writing it.
+    const GET_BLOCKS_RESULT: &[&str] = &[
+        "01", // get_blocks_result_v0
+        "b77c070000077cb769fc3f025a500ef3478ef9e65e401d05f9b9a7272c7ae3c3d0ea2b9e", // head, this block
+        "b67c070000077cb6d5534a23579751f578148b8f0f2da54cd22243b4d6c17ba398ab8a90", // last_irreversible, the previous block
+        "01b77c070000077cb769fc3f025a500ef3478ef9e65e401d05f9b9a7272c7ae3c3d0ea2b9e", // this_block
+        "01b67c070000077cb6d5534a23579751f578148b8f0f2da54cd22243b4d6c17ba398ab8a90", // prev_block
+        "01d802", // block, 344 bytes
+        // the block of `tampered_block_should_not_validate` in block.rs
+        "0f57684a0000000000ea3055000000077cb6d5534a23579751f578148b8f0f2da54cd22243b4d6c17ba398ab8a900096714e43362a3bf531eaf43114603689e5",
+        "561a36aa08225329eca7d939d22049b91659d7073782d1c456a29dde5ace92dffde0cfa78bb284e8d4d7f976fda1000000000000001f36f6f52520fa593f5678",
+        "26935186688d6bb6de7938ec8102c7f726bafe7cc8ae2b5585a3c8ee3a1e79011726b77a2b5f9a0593391ce7fc42c42b2e4a43cc011001005301000010010100",
+        "206b22f146d8bfe03a7a03b760cb2539409b05f9961543ee41c31f0cf493267b8c244d1517a6aa67cf47f294755d9e2fb5dda6779f5d88d6e4461f380a2b0296",
+        "4b000053256fa15db57c56c88ddb000000000100a6823403ea3055000000572d3ccdcd010000000000855c3400000000a8ed3232210000000000855c34000000",
+        "0000000e3d102700000000000004454f5300000000000000",
+        "018a05", // traces, 650 bytes
+        "01", // one transaction trace
+        "00", // transaction_trace_v0
+        "69569070707daf4b26cf07b657fa5b0b685ec503d33e96618a267e5f0eb86956", // id, sha256 of the packed transaction of the block
+        "00", // status executed
+        "53010000", // cpu_usage_us 339 like the receipt in the block
+        "10", // net_usage_words 16
+        "e204000000000000", // elapsed 1250
+        "8000000000000000", // net_usage 128
+        "00", // scheduled false
+        "03", // three action traces, the transfer and its two notifications
+        "00", // action_trace_v0, eosio.token
+        "0100", // action_ordinal 1, creator_action_ordinal 0
+        "0100", // some action_receipt_v0
+        "00a6823403ea3055", // receiver eosio.token
+        "4360bacdcd055bb14d3a5c5c1bec6c2b03ae4f1ddce6c8194c45a15fa909b670", // act_digest, sha256 of the packed action
+        "e8030000000000000300000000000000", // global_sequence 1000, recv_sequence 3
+        "010000000000855c340200000000000000", // auth_sequence alice 2
+        "0101", // code_sequence 1, abi_sequence 1
+        "00a6823403ea3055", // receiver
+        "00a6823403ea3055000000572d3ccdcd", // act, eosio.token transfer of the block
+        "010000000000855c3400000000a8ed3232", // authorization alice@active
+        "210000000000855c340000000000000e3d102700000000000004454f530000000000", // data, alice to bob 1.0000 EOS
+        "006400000000000000", // context_free false, elapsed 100
+        "00", // console empty
+        "00", // account_ram_deltas empty
+        "0000", // except and error_code none
+        "00", // action_trace_v0, alice
+        "0201", // action_ordinal 2, creator_action_ordinal 1
+        "0100", // some action_receipt_v0
+        "0000000000855c34", // receiver alice
+        "4360bacdcd055bb14d3a5c5c1bec6c2b03ae4f1ddce6c8194c45a15fa909b670", // act_digest, sha256 of the packed action
+        "e9030000000000000200000000000000", // global_sequence 1001, recv_sequence 2
+        "010000000000855c340300000000000000", // auth_sequence alice 3
+        "0000", // code_sequence 0, abi_sequence 0
+        "0000000000855c34", // receiver
+        "00a6823403ea3055000000572d3ccdcd", // act, eosio.token transfer of the block
+        "010000000000855c3400000000a8ed3232", // authorization alice@active
+        "210000000000855c340000000000000e3d102700000000000004454f530000000000", // data, alice to bob 1.0000 EOS
+        "006400000000000000", // context_free false, elapsed 100
+        "00", // console empty
+        "00", // account_ram_deltas empty
+        "0000", // except and error_code none
+        "00", // action_trace_v0, bob
+        "0301", // action_ordinal 3, creator_action_ordinal 1
+        "0100", // some action_receipt_v0
+        "0000000000000e3d", // receiver bob
+        "4360bacdcd055bb14d3a5c5c1bec6c2b03ae4f1ddce6c8194c45a15fa909b670", // act_digest, sha256 of the packed action
+        "ea030000000000000100000000000000", // global_sequence 1002, recv_sequence 1
+        "010000000000855c340400000000000000", // auth_sequence alice 4
+        "0000", // code_sequence 0, abi_sequence 0
+        "0000000000000e3d", // receiver
+        "00a6823403ea3055000000572d3ccdcd", // act, eosio.token transfer of the block
+        "010000000000855c3400000000a8ed3232", // authorization alice@active
+        "210000000000855c340000000000000e3d102700000000000004454f530000000000", // data, alice to bob 1.0000 EOS
+        "006400000000000000", // context_free false, elapsed 100
+        "00", // console empty
+        "00", // account_ram_deltas empty
+        "0000", // except and error_code none
+        "00", // account_ram_delta none
+        "0000", // except and error_code none
+        "00", // failed_dtrx_trace none
+        "0100", // some partial_transaction_v0
+        "256fa15db57c56c88ddb", // expiration, ref_block_num, ref_block_prefix
+        "000000", // max_net_usage_words, max_cpu_usage_ms, delay_sec
+        "00", // transaction_extensions empty
+        "01", // one signature
+        "00206b22f146d8bfe03a7a03b760cb2539409b05f9961543ee41c31f0cf493267b8c244d1517a6aa67cf47f294755d9e2fb5dda6779f5d88d6e4461f380a2b02964b", // the one of the block
+        "00", // context_free_data empty
+        "01d601", // deltas, 214 bytes
+        "02", // two table deltas
+        "000c636f6e74726163745f726f77", // table_delta_v0 contract_row
+        "02", // two rows
+        "013a", // present, 58 bytes
+        "0000a6823403ea30550000000000855c34000000384f4d1132", // contract_row_v0 eosio.token alice accounts
+        "454f5300000000000000000000855c34", // primary_key EOS, payer alice
+        "10905f01000000000004454f5300000000", // value, 9.0000 EOS
+        "013a", // present, 58 bytes
+        "0000a6823403ea30550000000000000e3d000000384f4d1132", // contract_row_v0 eosio.token bob accounts
+        "454f5300000000000000000000000e3d", // primary_key EOS, payer bob
+        "10102700000000000004454f5300000000", // value, 1.0000 EOS
+        "000e7265736f757263655f7573616765", // table_delta_v0 resource_usage
+        "01013b", // one row, present, 59 bytes
+        "000000000000855c34", // resource_usage_v0 alice
+        "00b77c07000020a107000000008000000000000000", // net_usage in block 490679, 128 bytes
+        "00b77c0700c0ba3414000000005301000000000000", // cpu_usage in block 490679, 339 us
+        "b40b000000000000", // ram_usage 2996 bytes
+    ];
+
+    #[test]
+    fn ship_blocks_result_fixture_should_be_decoded() {
+        let data = hex::decode(GET_BLOCKS_RESULT.concat()).unwrap();
+        let result = match unpack::<ShipResult>(&data) {
+            ShipResult::GetBlocksResultV0(result) => result,
+            result => panic!("expect blocks, got {:?}", result),
+        };
+        assert_eq!(pack(&ShipResult::GetBlocksResultV0(result.clone())), data);
+        let this_block = result.this_block.clone().unwrap();
+        let prev_block = result.prev_block.clone().unwrap();
+        assert_eq!(result.head, this_block);
+        assert_eq!(result.last_irreversible, prev_block);
+
+        let block = result.signed_block().unwrap().unwrap();
+        assert_eq!(block.block_num(), this_block.block_num);
+        assert_eq!(block.id().unwrap(), this_block.block_id);
+        assert_eq!(block.signed_block_header.block_header.previous, prev_block.block_id);
+        let receipt = &block.transactions[0];
+        let packed = match &receipt.trx {
+            TrxKinds::PackedTransaction(packed) => packed,
+            trx => panic!("expect a packed transaction, got {:?}", trx),
+        };
+
+        let traces = result.transaction_traces().unwrap().unwrap();
+        assert_eq!(traces.len(), 1);
+        let TransactionTrace::V0(trace) = &traces[0];
+        assert_eq!(trace.id, Checksum256::hash_from_slice(&packed.packed_trx));
+        assert_eq!(trace.cpu_usage_us, receipt.trx_receipt_header.cpu_usage_us);
+        assert_eq!(trace.net_usage_words, receipt.trx_receipt_header.net_usage_words);
+        let PartialTransaction::V0(partial) = trace.partial.as_ref().unwrap();
+        assert_eq!(partial.signatures, packed.signatures);
+
+        let token = AccountName::from_str("eosio.token").unwrap();
+        let alice = AccountName::from_str("alice").unwrap();
+        let bob = AccountName::from_str("bob").unwrap();
+        let receivers: Vec<_> = trace.action_traces.iter().map(ActionTrace::receiver).collect();
+        assert_eq!(receivers, vec![token, alice, bob]);
+        let act = trace.action_traces[0].act();
+        assert_eq!((act.account, act.name), (token, ActionName::from_str("transfer").unwrap()));
+        assert_eq!(act.authorization, vec![PermissionLevel::from_str("alice", "active").unwrap()]);
+        let transfer: (AccountName, AccountName, Asset, String) = unpack(&act.data);
+        assert_eq!(transfer, (alice, bob, Asset::from_str("1.0000 EOS").unwrap(), String::new()));
+        for action_trace in &trace.action_traces {
+            let action_trace = match action_trace {
+                ActionTrace::V0(action_trace) => action_trace,
+                action_trace => panic!("expect a v0 trace, got {:?}", action_trace),
+            };
+            let ShipActionReceipt::V0(receipt) = action_trace.receipt.as_ref().unwrap();
+            assert_eq!(receipt.receiver, action_trace.receiver);
+            assert_eq!(receipt.act_digest, Checksum256::hash(act.clone()).unwrap());
+        }
+
+        let deltas = result.table_deltas().unwrap().unwrap();
+        let TableDelta::V0(balances) = &deltas[0];
+        assert_eq!(balances.name, "contract_row");
+        let balances: Vec<_> = balances.rows.iter().map(|row| {
+            let ShipContractRow::V0(row) = row.decode::<ShipContractRow>().unwrap();
+            (AccountName::from(row.scope), unpack::<Asset>(&row.value).to_string())
+        }).collect();
+        assert_eq!(balances, vec![(alice, String::from("9.0000 EOS")), (bob, String::from("1.0000 EOS"))]);
+
+        let TableDelta::V0(usages) = &deltas[1];
+        assert_eq!(usages.name, "resource_usage");
+        let ShipResourceUsage::V0(usage) = usages.rows[0].decode::<ShipResourceUsage>().unwrap();
+        assert_eq!(usage.owner, alice);
+        let ShipUsageAccumulator::V0(cpu_usage) = usage.cpu_usage;
+        assert_eq!(cpu_usage.last_ordinal, this_block.block_num);
+        assert_eq!(cpu_usage.consumed, u64::from(trace.cpu_usage_us));
+        assert_eq!(usage.ram_usage, 2996);
+    }
+
+    #[test]
+    fn ship_blocks_result_should_work() {
+        let alice = AccountName::from_str("alice").unwrap();
+        let act = Action {
+            account: AccountName::from_str("eosio.token").unwrap(),
+            name: ActionName::from_str("transfer").unwrap(),
+            authorization: vec![PermissionLevel::from_str("alice", "active").unwrap()],
+            data: vec![1, 2, 3],
+        };
+        let receipt = ActionReceipt {
+            receiver: act.account,
+            act_digest: Checksum256::hash(act.clone()).unwrap(),
+            global_sequence: 10,
+            recv_sequence: 2,
+            auth_sequence: FlatMap::new(alice, 3),
+            code_sequence: UnsignedInt::from(1u32),
+            abi_sequence: UnsignedInt::from(1u32),
+        };
+        let action_trace = ActionTrace::V1(ActionTraceV1 {
+            action_ordinal: UnsignedInt::from(1u32),
+            receipt: Some(ShipActionReceipt::V0(receipt)),
+            receiver: act.account,
+            act,
+            console: String::from("hi"),
+            account_ram_deltas: vec![AccountDelta { account: alice, delta: 128 }],
+            return_value: vec![7],
+            ..Default::default()
+        });
+        let failed = TransactionTrace::V0(TransactionTraceV0 { status: 2, except: Some(String::from("assert")), ..Default::default() });
+        let trace = TransactionTrace::V0(TransactionTraceV0 {
+            id: Checksum256::hash_from_slice(b"transaction"),
+            cpu_usage_us: 100,
+            net_usage_words: UnsignedInt::from(16u32),
+            action_traces: vec![action_trace.clone()],
+            failed_dtrx_trace: Some(Box::new(failed.clone())),
+            ..Default::default()
+        });
+        let block = SignedBlock::new(SignedBlockHeader::default());
+        let position = BlockPosition { block_num: 2, block_id: block.id().unwrap() };
+
+        let result = ShipResult::GetBlocksResultV0(GetBlocksResultV0 {
+            head: position.clone(),
+            last_irreversible: position.clone(),
+            this_block: Some(position),
+            prev_block: None,
+            block: Some(block.to_serialize_data().unwrap()),
+            traces: Some(pack(&vec![trace.clone()])),
+            deltas: None,
+        });
+        let decoded = match unpack::<ShipResult>(&pack(&result)) {
+            ShipResult::GetBlocksResultV0(decoded) => decoded,
+            result => panic!("expect blocks, got {:?}", result),
+        };
+        assert_eq!(decoded.signed_block().unwrap(), Some(block));
+        assert_eq!(decoded.table_deltas().unwrap(), None);
+
+        let traces = decoded.transaction_traces().unwrap().unwrap();
+        assert_eq!(traces, vec![trace]);
+        let TransactionTrace::V0(trace) = &traces[0];
+        assert_eq!(trace.action_traces[0].receiver(), AccountName::from_str("eosio.token").unwrap());
+        assert_eq!(trace.action_traces[0].act().data, vec![1, 2, 3]);
+        assert_eq!(trace.failed_dtrx_trace.as_deref(), Some(&failed));
+    }
+}
@@ -0,0 +1,627 @@
+//! Read the binary portable snapshots of nodeos 2.0 section by section.
+//! <https://github.com/EOSIO/eos/blob/v2.0.9/libraries/chain/snapshot.cpp>
+//!
+//! A snapshot starts with a magic number and the format version. Each section follows:
+//! its size, its row count, its null terminated name and the packed rows. An end marker
+//! closes the file. Rows are read in chunks so large sections don't have to fit in memory.
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read as IoRead, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::path::Path;
+use crate::{
+    utils::flat_map::FlatMap, AccountName, Authority, BlockHeaderState, BlockSigningAuthority,
+    BlockTimestamp, Checksum256, IncrementalMerkle, NumBytes, PermissionName, ProducerAuthoritySchedule,
+    Read, ReadError, ScheduleInfo, ScopeName, Signature, SignedBlockHeader, TableName, TimePoint,
+    UnsignedInt, Write
+};
+
+pub const SNAPSHOT_MAGIC: u32 = 0x3051_0550;
+/// Version of the file format.
+pub const SNAPSHOT_VERSION: u32 = 1;
+/// Version of `chain_snapshot_header` written by nodeos 2.0, the one the typed sections follow.
+pub const SNAPSHOT_CHAIN_VERSION: u32 = 3;
+const SNAPSHOT_END_MARKER: u64 = u64::MAX;
+/// Rows are read from the file this many bytes at least at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+pub const CHAIN_SNAPSHOT_HEADER_SECTION: &str = "eosio::chain::chain_snapshot_header";
+pub const BLOCK_STATE_SECTION: &str = "eosio::chain::block_state";
+pub const ACCOUNT_SECTION: &str = "eosio::chain::account_object";
+pub const PERMISSION_SECTION: &str = "eosio::chain::permission_object";
+pub const CONTRACT_TABLES_SECTION: &str = "contract_tables";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The file doesn't start with the snapshot magic number
+    InvalidMagic,
+    /// The file format version is not supported
+    UnsupportedVersion(u32),
+    /// The `chain_snapshot_header` version is not the one of nodeos 2.0
+    UnsupportedChainVersion(u32),
+    /// The section header at this position is malformed
+    InvalidSection(u64),
+    /// The snapshot has no such section
+    MissingSection,
+}
+
+impl From<SnapshotError> for crate::Error {
+    fn from(e: SnapshotError) -> crate::Error {
+        crate::Error::SnapshotError(e)
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct ChainSnapshotHeader {
+    pub version: u32,
+}
+
+/// The `block_header_state` of the head block, the snapshot resumes from it.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct SnapshotBlockHeaderState {
+    pub block_num: u32,
+    pub dpos_proposed_irreversible_blocknum: u32,
+    pub dpos_irreversible_blocknum: u32,
+    pub active_schedule: ProducerAuthoritySchedule,
+    pub blockroot_merkle: IncrementalMerkle,
+    pub producer_to_last_produced: FlatMap<AccountName, u32>,
+    pub producer_to_last_implied_irb: FlatMap<AccountName, u32>,
+    pub valid_block_signing_authority: BlockSigningAuthority,
+    pub confirm_count: Vec<u8>,
+    pub id: Checksum256,
+    pub header: SignedBlockHeader,
+    pub pending_schedule: ScheduleInfo,
+    pub activated_protocol_features: Vec<Checksum256>,
+    pub additional_signatures: Vec<Signature>,
+}
+
+/// The head block state, e.g. to start a `LightClient` or a `ForkDatabase` from a snapshot.
+impl From<SnapshotBlockHeaderState> for BlockHeaderState {
+    fn from(state: SnapshotBlockHeaderState) -> Self {
+        BlockHeaderState {
+            id: state.id,
+            block_num: state.block_num,
+            header: state.header,
+            dpos_proposed_irreversible_blocknum: state.dpos_proposed_irreversible_blocknum,
+            dpos_irreversible_blocknum: state.dpos_irreversible_blocknum,
+            active_schedule: state.active_schedule,
+            pending_schedule: state.pending_schedule,
+            blockroot_merkle: state.blockroot_merkle,
+            producer_to_last_produced: state.producer_to_last_produced.maps.into_iter().collect(),
+            producer_to_last_implied_irb: state.producer_to_last_implied_irb.maps.into_iter().collect(),
+            valid_block_signing_authority: state.valid_block_signing_authority,
+            additional_signatures: state.additional_signatures,
+            confirm_count: state.confirm_count,
+        }
+    }
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct AccountObject {
+    pub name: AccountName,
+    pub creation_date: BlockTimestamp,
+    pub abi: Vec<u8>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct PermissionObject {
+    /// Empty for the `owner` permission
+    pub parent: PermissionName,
+    pub owner: AccountName,
+    pub name: PermissionName,
+    pub last_updated: TimePoint,
+    pub last_used: TimePoint,
+    pub auth: Authority,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct TableIdObject {
+    pub code: AccountName,
+    pub scope: ScopeName,
+    pub table: TableName,
+    pub payer: AccountName,
+    pub count: u32,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct KeyValueObject {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub value: Vec<u8>,
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct Index64Object {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: u64,
+}
+
+/// The secondary key is a little endian `uint128_t`.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct Index128Object {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: [u8; 16],
+}
+
+/// The secondary key is two little endian `uint128_t`.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct Index256Object {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: [u8; 32],
+}
+
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct IndexDoubleObject {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: f64,
+}
+
+/// The secondary key is the raw bytes of a `float128_t`.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio_core_root_path = "crate"]
+pub struct IndexLongDoubleObject {
+    pub primary_key: u64,
+    pub payer: AccountName,
+    pub secondary_key: [u8; 16],
+}
+
+/// A contract table with its rows and the rows of its secondary indices.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContractTable {
+    pub table: TableIdObject,
+    pub key_values: Vec<KeyValueObject>,
+    pub index64: Vec<Index64Object>,
+    pub index128: Vec<Index128Object>,
+    pub index256: Vec<Index256Object>,
+    pub index_double: Vec<IndexDoubleObject>,
+    pub index_long_double: Vec<IndexLongDoubleObject>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnapshotSection {
+    pub name: String,
+    pub row_count: u64,
+    /// Position of the first row in the file
+    pub offset: u64,
+    /// Bytes of all the rows
+    pub size: u64,
+}
+
+pub struct SnapshotReader {
+    file: BufReader<File>,
+    sections: Vec<SnapshotSection>,
+}
+
+impl SnapshotReader {
+    /// Open a snapshot and list its sections.
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        if u32::read(&header, &mut 0).map_err(crate::Error::BytesReadError)? != SNAPSHOT_MAGIC {
+            return Err(SnapshotError::InvalidMagic.into());
+        }
+        let version = u32::read(&header, &mut 4).map_err(crate::Error::BytesReadError)?;
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version).into());
+        }
+
+        let mut sections = Vec::new();
+        let mut position = header.len() as u64;
+        loop {
+            let mut size = [0u8; 8];
+            file.read_exact(&mut size)?;
+            let size = u64::from_le_bytes(size);
+            if size == SNAPSHOT_END_MARKER {
+                break;
+            }
+            let mut row_count = [0u8; 8];
+            file.read_exact(&mut row_count)?;
+            let mut name = Vec::new();
+            file.read_until(0, &mut name)?;
+            if name.pop() != Some(0) {
+                return Err(SnapshotError::InvalidSection(position).into());
+            }
+            let name = String::from_utf8(name).map_err(|_| SnapshotError::InvalidSection(position))?;
+
+            // the size counts everything after itself
+            let offset = position + 16 + name.len() as u64 + 1;
+            let end = position + 8 + size;
+            if end < offset {
+                return Err(SnapshotError::InvalidSection(position).into());
+            }
+            sections.push(SnapshotSection {
+                name,
+                row_count: u64::from_le_bytes(row_count),
+                offset,
+                size: end - offset,
+            });
+            file.seek(SeekFrom::Start(end))?;
+            position = end;
+        }
+
+        Ok(SnapshotReader { file, sections })
+    }
+
+    pub fn sections(&self) -> &[SnapshotSection] {
+        &self.sections
+    }
+
+    pub fn section(&self, name: &str) -> Option<&SnapshotSection> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// The rows of any section, `T` is the packed row type.
+    pub fn rows<T: Read>(&mut self, name: &str) -> crate::Result<SnapshotRows<'_, T>> {
+        let (row_count, reader) = self.row_reader(name)?;
+        Ok(SnapshotRows { reader, remaining: row_count, row: PhantomData })
+    }
+
+    pub fn chain_snapshot_header(&mut self) -> crate::Result<ChainSnapshotHeader> {
+        self.rows(CHAIN_SNAPSHOT_HEADER_SECTION)?.next()
+            .unwrap_or_else(|| Err(SnapshotError::MissingSection.into()))
+    }
+
+    pub fn block_state(&mut self) -> crate::Result<SnapshotBlockHeaderState> {
+        self.check_chain_version()?;
+        self.rows(BLOCK_STATE_SECTION)?.next()
+            .unwrap_or_else(|| Err(SnapshotError::MissingSection.into()))
+    }
+
+    pub fn accounts(&mut self) -> crate::Result<SnapshotRows<'_, AccountObject>> {
+        self.check_chain_version()?;
+        self.rows(ACCOUNT_SECTION)
+    }
+
+    pub fn permissions(&mut self) -> crate::Result<SnapshotRows<'_, PermissionObject>> {
+        self.check_chain_version()?;
+        self.rows(PERMISSION_SECTION)
+    }
+
+    pub fn contract_tables(&mut self) -> crate::Result<ContractTables<'_>> {
+        self.check_chain_version()?;
+        let (remaining, reader) = self.row_reader(CONTRACT_TABLES_SECTION)?;
+        Ok(ContractTables { reader, remaining })
+    }
+
+    fn check_chain_version(&mut self) -> crate::Result<()> {
+        let version = self.chain_snapshot_header()?.version;
+        if version != SNAPSHOT_CHAIN_VERSION {
+            return Err(SnapshotError::UnsupportedChainVersion(version).into());
+        }
+        Ok(())
+    }
+
+    fn row_reader(&mut self, name: &str) -> crate::Result<(u64, RowReader<'_>)> {
+        let section = self.section(name).cloned().ok_or(SnapshotError::MissingSection)?;
+        self.file.seek(SeekFrom::Start(section.offset))?;
+        let reader = RowReader { file: &mut self.file, remaining: section.size, buf: Vec::new(), pos: 0 };
+        Ok((section.row_count, reader))
+    }
+}
+
+/// Rows of a section, read from the file as they are needed.
+struct RowReader<'a> {
+    file: &'a mut BufReader<File>,
+    /// Bytes of the section not read from the file yet
+    remaining: u64,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<'a> RowReader<'a> {
+    fn read_row<T: Read>(&mut self) -> crate::Result<T> {
+        loop {
+            let mut pos = self.pos;
+            match T::read(&self.buf, &mut pos) {
+                Ok(row) => {
+                    self.pos = pos;
+                    return Ok(row);
+                }
+                Err(ReadError::NotEnoughBytes) if self.remaining > 0 => self.fill()?,
+                Err(e) => return Err(crate::Error::BytesReadError(e)),
+            }
+        }
+    }
+
+    fn read_rows<T: Read>(&mut self, count: usize) -> crate::Result<Vec<T>> {
+        (0..count).map(|_| self.read_row()).collect()
+    }
+
+    /// Read more of the section, at least as much as buffered so a large row is
+    /// read in a few steps.
+    fn fill(&mut self) -> crate::Result<()> {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let len = self.remaining.min(CHUNK_SIZE.max(self.buf.len()) as u64);
+        let start = self.buf.len();
+        self.buf.resize(start + len as usize, 0);
+        self.file.read_exact(&mut self.buf[start..])?;
+        self.remaining -= len;
+        Ok(())
+    }
+}
+
+pub struct SnapshotRows<'a, T> {
+    reader: RowReader<'a>,
+    remaining: u64,
+    row: PhantomData<T>,
+}
+
+impl<'a, T: Read> Iterator for SnapshotRows<'a, T> {
+    type Item = crate::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.reader.read_row())
+    }
+}
+
+/// The tables of the `contract_tables` section. Each table is a row followed, for the
+/// primary index then every secondary index, by a row with the row count and the rows.
+pub struct ContractTables<'a> {
+    reader: RowReader<'a>,
+    remaining: u64,
+}
+
+impl<'a> ContractTables<'a> {
+    fn read_table(&mut self) -> crate::Result<ContractTable> {
+        let table = self.reader.read_row()?;
+        let key_values = self.read_index()?;
+        let index64 = self.read_index()?;
+        let index128 = self.read_index()?;
+        let index256 = self.read_index()?;
+        let index_double = self.read_index()?;
+        let index_long_double = self.read_index()?;
+        Ok(ContractTable { table, key_values, index64, index128, index256, index_double, index_long_double })
+    }
+
+    fn read_index<T: Read>(&mut self) -> crate::Result<Vec<T>> {
+        let size = usize::from(self.reader.read_row::<UnsignedInt>()?);
+        let rows = self.reader.read_rows(size)?;
+        self.remaining = self.remaining.saturating_sub(size as u64 + 1);
+        Ok(rows)
+    }
+}
+
+impl<'a> Iterator for ContractTables<'a> {
+    type Item = crate::Result<ContractTable>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let table = self.read_table();
+        if table.is_err() {
+            self.remaining = 0;
+        }
+        Some(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use std::path::PathBuf;
+    use crate::{KeyWeight, PublicKey};
+    use crate::test_utils::{public_key, WIFS};
+
+    fn pack<T: Write + NumBytes>(row: &T) -> Vec<u8> {
+        let mut data = vec![0u8; row.num_bytes()];
+        row.write(&mut data, &mut 0).unwrap();
+        data
+    }
+
+    fn section(name: &str, rows: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = (rows.len() as u64).to_le_bytes().to_vec();
+        body.extend_from_slice(name.as_bytes());
+        body.push(0);
+        for row in rows {
+            body.extend_from_slice(row);
+        }
+        let mut data = (body.len() as u64).to_le_bytes().to_vec();
+        data.extend(body);
+        data
+    }
+
+    fn write_snapshot(name: &str, chain_version: u32, sections: &[Vec<u8>]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.bin", name, std::process::id()));
+        let mut data = SNAPSHOT_MAGIC.to_le_bytes().to_vec();
+        data.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        data.extend(section(CHAIN_SNAPSHOT_HEADER_SECTION, &[pack(&ChainSnapshotHeader { version: chain_version })]));
+        for section in sections {
+            data.extend_from_slice(section);
+        }
+        data.extend_from_slice(&SNAPSHOT_END_MARKER.to_le_bytes());
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn permission(owner: &str, name: &str, key: &PublicKey) -> PermissionObject {
+        PermissionObject {
+            parent: PermissionName::from_str(if name == "owner" { "" } else { "owner" }).unwrap(),
+            owner: AccountName::from_str(owner).unwrap(),
+            name: PermissionName::from_str(name).unwrap(),
+            last_updated: TimePoint::from(0),
+            last_used: TimePoint::from(0),
+            auth: Authority {
+                threshold: 1,
+                keys: vec![KeyWeight { key: key.clone(), weight: 1 }],
+                accounts: vec![],
+                waits: vec![],
+            },
+        }
+    }
+
+    fn key_value(primary_key: u64, value: Vec<u8>) -> KeyValueObject {
+        KeyValueObject { primary_key, payer: AccountName::from_str("alice").unwrap(), value }
+    }
+
+    #[test]
+    fn snapshot_should_be_read() {
+        let key = public_key(WIFS[0]);
+        let other_key = public_key(WIFS[1]);
+
+        let state = SnapshotBlockHeaderState {
+            block_num: 42,
+            dpos_irreversible_blocknum: 40,
+            producer_to_last_produced: FlatMap::new(AccountName::from_str("eosio").unwrap(), 42),
+            id: Checksum256::hash_from_slice(b"block 42"),
+            activated_protocol_features: vec![Checksum256::hash_from_slice(b"feature")],
+            ..Default::default()
+        };
+
+        let accounts: Vec<AccountObject> = ["alice", "bob", "carol"].iter().map(|name| AccountObject {
+            name: AccountName::from_str(name).unwrap(),
+            creation_date: BlockTimestamp(1_000),
+            abi: vec![],
+        }).collect();
+        let permissions = [
+            permission("alice", "owner", &key),
+            permission("alice", "active", &other_key),
+            permission("bob", "active", &other_key),
+            permission("carol", "active", &key),
+        ];
+
+        // a value larger than a chunk is read in several steps
+        let token = TableIdObject {
+            code: AccountName::from_str("eosio.token").unwrap(),
+            scope: ScopeName::from_str("alice").unwrap(),
+            table: TableName::from_str("accounts").unwrap(),
+            payer: AccountName::from_str("alice").unwrap(),
+            count: 2,
+        };
+        let token_rows = vec![key_value(1, vec![1; 8]), key_value(2, vec![2; CHUNK_SIZE * 2 + 1])];
+        let index64 = vec![Index64Object { primary_key: 1, payer: token.payer, secondary_key: 7 }];
+        let mut table_rows = vec![pack(&token), pack(&UnsignedInt::from(2u32))];
+        table_rows.extend(token_rows.iter().map(pack));
+        table_rows.extend(vec![pack(&UnsignedInt::from(1u32)), pack(&index64[0])]);
+        table_rows.extend((0..4).map(|_| pack(&UnsignedInt::from(0u32))));
+        let empty = TableIdObject { table: TableName::from_str("stat").unwrap(), count: 0, ..token.clone() };
+        table_rows.push(pack(&empty));
+        table_rows.extend((0..6).map(|_| pack(&UnsignedInt::from(0u32))));
+
+        let path = write_snapshot("snapshot_should_be_read", SNAPSHOT_CHAIN_VERSION, &[
+            section(BLOCK_STATE_SECTION, &[pack(&state)]),
+            section(ACCOUNT_SECTION, &accounts.iter().map(pack).collect::<Vec<_>>()),
+            section("eosio::chain::code_object", &[vec![1, 2, 3]]),
+            section(PERMISSION_SECTION, &permissions.iter().map(pack).collect::<Vec<_>>()),
+            section(CONTRACT_TABLES_SECTION, &table_rows),
+        ]);
+
+        let mut reader = SnapshotReader::open(&path).unwrap();
+        let names: Vec<&str> = reader.sections().iter().map(|section| section.name.as_str()).collect();
+        assert_eq!(names, vec![
+            CHAIN_SNAPSHOT_HEADER_SECTION,
+            BLOCK_STATE_SECTION,
+            ACCOUNT_SECTION,
+            "eosio::chain::code_object",
+            PERMISSION_SECTION,
+            CONTRACT_TABLES_SECTION,
+        ]);
+        assert_eq!(reader.section("eosio::chain::code_object").unwrap().size, 3);
+        assert_eq!(reader.chain_snapshot_header().unwrap().version, SNAPSHOT_CHAIN_VERSION);
+
+        let read_state = reader.block_state().unwrap();
+        assert_eq!(read_state, state);
+        let block_header_state = BlockHeaderState::from(read_state);
+        assert_eq!(block_header_state.dpos_irreversible_blocknum, 40);
+        assert_eq!(block_header_state.producer_to_last_produced.get(&AccountName::from_str("eosio").unwrap()), Some(&42));
+
+        let read_accounts: Vec<AccountObject> = reader.accounts().unwrap().map(Result::unwrap).collect();
+        assert_eq!(read_accounts, accounts);
+
+        // which accounts use this key
+        let mut owners: Vec<AccountName> = reader.permissions().unwrap()
+            .map(Result::unwrap)
+            .filter(|permission| permission.auth.keys.iter().any(|weight| weight.key == key))
+            .map(|permission| permission.owner)
+            .collect();
+        owners.dedup();
+        assert_eq!(owners, vec![AccountName::from_str("alice").unwrap(), AccountName::from_str("carol").unwrap()]);
+
+        let tables: Vec<ContractTable> = reader.contract_tables().unwrap().map(Result::unwrap).collect();
+        assert_eq!(tables.len(), 2);
+        assert_eq!(tables[0].table, token);
+        assert_eq!(tables[0].key_values, token_rows);
+        assert_eq!(tables[0].index64, index64);
+        assert!(tables[0].index128.is_empty());
+        assert_eq!(tables[1], ContractTable { table: empty, ..Default::default() });
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn permission_row_should_follow_nodeos_layout() {
+        let key = public_key(WIFS[0]);
+        let name = |name: &str| PermissionName::from_str(name).unwrap().as_u64().to_le_bytes();
+
+        // snapshot_permission_object is (parent)(owner)(name)(last_updated)(last_used)(auth)
+        let mut row = Vec::new();
+        row.extend_from_slice(&name("owner"));
+        row.extend_from_slice(&AccountName::from_str("alice").unwrap().as_u64().to_le_bytes());
+        row.extend_from_slice(&name("active"));
+        row.extend_from_slice(&1_600_000_000_000_000i64.to_le_bytes());
+        row.extend_from_slice(&1_600_000_500_000_000i64.to_le_bytes());
+        // authority: threshold, one key weight, no accounts and no waits
+        row.extend_from_slice(&1u32.to_le_bytes());
+        row.push(1);
+        row.push(0);
+        row.extend_from_slice(&key.data);
+        row.extend_from_slice(&1u16.to_le_bytes());
+        row.extend_from_slice(&[0, 0]);
+
+        let path = write_snapshot("permission_row", SNAPSHOT_CHAIN_VERSION, &[section(PERMISSION_SECTION, &[row.clone()])]);
+        let mut reader = SnapshotReader::open(&path).unwrap();
+        let permissions: Vec<PermissionObject> = reader.permissions().unwrap().map(Result::unwrap).collect();
+        let expected = PermissionObject {
+            last_updated: TimePoint::from(1_600_000_000_000_000),
+            last_used: TimePoint::from(1_600_000_500_000_000),
+            ..permission("alice", "active", &key)
+        };
+        assert_eq!(permissions, vec![expected.clone()]);
+        assert_eq!(pack(&expected), row);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unsupported_snapshot_should_be_rejected() {
+        let path = write_snapshot("unsupported_snapshot", 2, &[section(ACCOUNT_SECTION, &[])]);
+        let mut reader = SnapshotReader::open(&path).unwrap();
+        match reader.accounts() {
+            Err(crate::Error::SnapshotError(SnapshotError::UnsupportedChainVersion(2))) => {}
+            _ => panic!("the snapshot is older than nodeos 2.0"),
+        }
+        match reader.rows::<u32>(PERMISSION_SECTION) {
+            Err(crate::Error::SnapshotError(SnapshotError::MissingSection)) => {}
+            _ => panic!("the snapshot has no permissions"),
+        }
+
+        let mut data = std::fs::read(&path).unwrap();
+        data[0] = 0;
+        std::fs::write(&path, data).unwrap();
+        match SnapshotReader::open(&path) {
+            Err(crate::Error::SnapshotError(SnapshotError::InvalidMagic)) => {}
+            _ => panic!("the magic number is wrong"),
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,454 @@
+//! Actions of the eosio system contract
+//! <https://github.com/EOSIO/eosio.contracts/blob/master/contracts/eosio.system/include/eosio.system/eosio.system.hpp>
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::{
+    AccountName, ActionName, Asset, Authority, Checksum256, NumBytes, PermissionLevel,
+    PermissionName, PublicKey, Read, SerializeData, ToAction, Write
+};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The account the system contract is deployed to, `eosio`.
+pub const SYSTEM_CONTRACT: u64 = 6_138_663_577_826_885_632;
+
+/// Create an account with the given owner and active authorities.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct NewAccount {
+    pub creator: AccountName,
+    pub name: AccountName,
+    pub owner: Authority,
+    pub active: Authority,
+}
+
+impl ToAction for NewAccount {
+    /// `newaccount`
+    const NAME: u64 = 11_148_770_977_341_390_848;
+}
+
+/// Create or replace a permission of an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct UpdateAuth {
+    pub account: AccountName,
+    pub permission: PermissionName,
+    pub parent: PermissionName,
+    pub auth: Authority,
+}
+
+impl ToAction for UpdateAuth {
+    /// `updateauth`
+    const NAME: u64 = 15_371_467_950_649_982_976;
+}
+
+/// Delete a permission of an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct DeleteAuth {
+    pub account: AccountName,
+    pub permission: PermissionName,
+}
+
+impl ToAction for DeleteAuth {
+    /// `deleteauth`
+    const NAME: u64 = 5_378_050_746_259_030_016;
+}
+
+/// Require a permission to authorize an action of a contract.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct LinkAuth {
+    pub account: AccountName,
+    pub code: AccountName,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: ActionName,
+    pub requirement: PermissionName,
+}
+
+impl ToAction for LinkAuth {
+    /// `linkauth`
+    const NAME: u64 = 10_063_015_651_234_021_376;
+}
+
+/// Remove a link made by `linkauth`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct UnlinkAuth {
+    pub account: AccountName,
+    pub code: AccountName,
+    #[cfg_attr(feature = "std", serde(rename = "type"))]
+    pub type_: ActionName,
+}
+
+impl ToAction for UnlinkAuth {
+    /// `unlinkauth`
+    const NAME: u64 = 15_340_080_295_291_076_608;
+}
+
+/// Buy RAM for `receiver` worth `quant` tokens.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct BuyRam {
+    pub payer: AccountName,
+    pub receiver: AccountName,
+    pub quant: Asset,
+}
+
+impl ToAction for BuyRam {
+    /// `buyram`
+    const NAME: u64 = 4_520_896_354_024_685_568;
+}
+
+/// Buy `bytes` of RAM for `receiver`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct BuyRamBytes {
+    pub payer: AccountName,
+    pub receiver: AccountName,
+    pub bytes: u32,
+}
+
+impl ToAction for BuyRamBytes {
+    /// `buyrambytes`
+    const NAME: u64 = 4_520_896_358_299_381_760;
+}
+
+/// Sell `bytes` of RAM.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct SellRam {
+    pub account: AccountName,
+    pub bytes: i64,
+}
+
+impl ToAction for SellRam {
+    /// `sellram`
+    const NAME: u64 = 14_025_084_013_874_511_872;
+}
+
+/// Stake tokens for NET and CPU of `receiver`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct DelegateBw {
+    pub from: AccountName,
+    pub receiver: AccountName,
+    pub stake_net_quantity: Asset,
+    pub stake_cpu_quantity: Asset,
+    pub transfer: bool,
+}
+
+impl ToAction for DelegateBw {
+    /// `delegatebw`
+    const NAME: u64 = 5_378_043_540_636_893_184;
+}
+
+/// Unstake tokens from NET and CPU of `receiver`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct UndelegateBw {
+    pub from: AccountName,
+    pub receiver: AccountName,
+    pub unstake_net_quantity: Asset,
+    pub unstake_cpu_quantity: Asset,
+}
+
+impl ToAction for UndelegateBw {
+    /// `undelegatebw`
+    const NAME: u64 = 15_335_505_127_214_321_600;
+}
+
+/// Claim unstaked tokens after the refund delay.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct Refund {
+    pub owner: AccountName,
+}
+
+impl ToAction for Refund {
+    /// `refund`
+    const NAME: u64 = 13_445_401_734_377_635_840;
+}
+
+/// Vote for producers, or delegate the vote to `proxy` with an empty producer list.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct VoteProducer {
+    pub voter: AccountName,
+    pub proxy: AccountName,
+    pub producers: Vec<AccountName>,
+}
+
+impl ToAction for VoteProducer {
+    /// `voteproducer`
+    const NAME: u64 = 15_938_989_903_989_314_928;
+}
+
+/// Register or update a block producer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct RegProducer {
+    pub producer: AccountName,
+    pub producer_key: PublicKey,
+    pub url: String,
+    pub location: u16,
+}
+
+impl ToAction for RegProducer {
+    /// `regproducer`
+    const NAME: u64 = 13_445_879_116_675_067_392;
+}
+
+/// Deactivate a block producer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct UnregProd {
+    pub producer: AccountName,
+}
+
+impl ToAction for UnregProd {
+    /// `unregprod`
+    const NAME: u64 = 15_343_383_872_893_616_128;
+}
+
+/// Claim block and vote rewards of a producer.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct ClaimRewards {
+    pub owner: AccountName,
+}
+
+impl ToAction for ClaimRewards {
+    /// `claimrewards`
+    const NAME: u64 = 4_921_565_079_997_371_264;
+}
+
+/// Deploy WASM code to an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct SetCode {
+    pub account: AccountName,
+    pub vmtype: u8,
+    pub vmversion: u8,
+    pub code: Vec<u8>,
+}
+
+impl ToAction for SetCode {
+    /// `setcode`
+    const NAME: u64 = 14_029_427_681_804_681_216;
+}
+
+/// Set the packed ABI of an account.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct SetAbi {
+    pub account: AccountName,
+    pub abi: Vec<u8>,
+}
+
+impl ToAction for SetAbi {
+    /// `setabi`
+    const NAME: u64 = 14_029_385_431_137_648_640;
+}
+
+/// Cancel a deferred transaction.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Read, Write, NumBytes, PartialEq, Default, SerializeData)]
+#[eosio_core_root_path = "crate"]
+pub struct CancelDelay {
+    pub canceling_auth: PermissionLevel,
+    pub trx_id: Checksum256,
+}
+
+impl ToAction for CancelDelay {
+    /// `canceldelay`
+    const NAME: u64 = 4_730_614_990_712_192_000;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::str::FromStr;
+    use crate::{KeyWeight, PermissionLevelWeight, WaitWeight};
+
+    const PUBLIC_KEY: &str = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+
+    fn parse<T: FromStr>(s: &str) -> T where T::Err: core::fmt::Debug {
+        T::from_str(s).unwrap()
+    }
+
+    fn assert_action_data<T: ToAction + SerializeData>(payload: T, action_name: &str, expected: &str) {
+        let authorization = vec![PermissionLevel::from_str("alice", "active").unwrap()];
+        let action = payload.to_action(SYSTEM_CONTRACT.into(), authorization).unwrap();
+        assert_eq!(action.account.to_string(), "eosio");
+        assert_eq!(action.name.to_string(), action_name);
+        assert_eq!(hex::encode(&action.data), expected);
+        assert_eq!(hex::encode(payload.to_serialize_data().unwrap()), expected);
+    }
+
+    #[test]
+    fn newaccount_should_work() {
+        let auth = Authority::from_key(PublicKey::from_str(PUBLIC_KEY).unwrap());
+        let newaccount = NewAccount {
+            creator: parse("eosio"),
+            name: parse("alice"),
+            owner: auth.clone(),
+            active: auth,
+        };
+        let expected = "0000000000ea30550000000000855c3401000000010002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf0100000001000000010002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf01000000";
+        assert_eq!(NewAccount::read(&hex::decode(expected).unwrap(), &mut 0).unwrap(), newaccount);
+        assert_action_data(newaccount, "newaccount", expected);
+    }
+
+    #[test]
+    fn updateauth_should_work() {
+        let updateauth = UpdateAuth {
+            account: parse("alice"),
+            permission: parse("active"),
+            parent: parse("owner"),
+            auth: Authority {
+                threshold: 2,
+                keys: vec![KeyWeight { key: PublicKey::from_str(PUBLIC_KEY).unwrap(), weight: 1 }],
+                accounts: vec![PermissionLevelWeight {
+                    permission: PermissionLevel::from_str("bob", "active").unwrap(),
+                    weight: 1,
+                }],
+                waits: vec![WaitWeight { wait_sec: 3600, weight: 1 }],
+            },
+        };
+        let expected = "0000000000855c3400000000a8ed32320000000080ab26a702000000010002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf0100010000000000000e3d00000000a8ed3232010001100e00000100";
+        assert_eq!(UpdateAuth::read(&hex::decode(expected).unwrap(), &mut 0).unwrap(), updateauth);
+        assert_action_data(updateauth, "updateauth", expected);
+    }
+
+    #[test]
+    fn permission_actions_should_work() {
+        assert_action_data(
+            DeleteAuth { account: parse("alice"), permission: parse("custom") },
+            "deleteauth",
+            "0000000000855c3400000000489ab146",
+        );
+        assert_action_data(
+            LinkAuth { account: parse("alice"), code: parse("eosio.token"), type_: parse("transfer"), requirement: parse("custom") },
+            "linkauth",
+            "0000000000855c3400a6823403ea3055000000572d3ccdcd00000000489ab146",
+        );
+        assert_action_data(
+            UnlinkAuth { account: parse("alice"), code: parse("eosio.token"), type_: parse("transfer") },
+            "unlinkauth",
+            "0000000000855c3400a6823403ea3055000000572d3ccdcd",
+        );
+        assert_action_data(
+            CancelDelay {
+                canceling_auth: PermissionLevel::from_str("alice", "active").unwrap(),
+                trx_id: Checksum256::new([
+                    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+                    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+                ]),
+            },
+            "canceldelay",
+            "0000000000855c3400000000a8ed3232000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        );
+    }
+
+    #[test]
+    fn resource_actions_should_work() {
+        assert_action_data(
+            BuyRam { payer: parse("alice"), receiver: parse("bob"), quant: parse("1.0000 EOS") },
+            "buyram",
+            "0000000000855c340000000000000e3d102700000000000004454f5300000000",
+        );
+        assert_action_data(
+            BuyRamBytes { payer: parse("alice"), receiver: parse("bob"), bytes: 8192 },
+            "buyrambytes",
+            "0000000000855c340000000000000e3d00200000",
+        );
+        assert_action_data(
+            SellRam { account: parse("alice"), bytes: 4096 },
+            "sellram",
+            "0000000000855c340010000000000000",
+        );
+        assert_action_data(
+            DelegateBw {
+                from: parse("alice"),
+                receiver: parse("bob"),
+                stake_net_quantity: parse("1.0000 EOS"),
+                stake_cpu_quantity: parse("2.5000 EOS"),
+                transfer: true,
+            },
+            "delegatebw",
+            "0000000000855c340000000000000e3d102700000000000004454f5300000000a86100000000000004454f530000000001",
+        );
+        assert_action_data(
+            UndelegateBw {
+                from: parse("alice"),
+                receiver: parse("bob"),
+                unstake_net_quantity: parse("1.0000 EOS"),
+                unstake_cpu_quantity: parse("2.5000 EOS"),
+            },
+            "undelegatebw",
+            "0000000000855c340000000000000e3d102700000000000004454f5300000000a86100000000000004454f5300000000",
+        );
+        assert_action_data(Refund { owner: parse("alice") }, "refund", "0000000000855c34");
+    }
+
+    #[test]
+    fn producer_actions_should_work() {
+        assert_action_data(
+            VoteProducer { voter: parse("alice"), proxy: AccountName::default(), producers: vec![parse("bp1"), parse("bp2")] },
+            "voteproducer",
+            "0000000000855c34000000000000000002000000000000423d000000000000443d",
+        );
+        let regproducer = RegProducer {
+            producer: parse("bp1"),
+            producer_key: PublicKey::from_str(PUBLIC_KEY).unwrap(),
+            url: "https://bp1.io".into(),
+            location: 840,
+        };
+        let expected = "000000000000423d0002c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf0e68747470733a2f2f6270312e696f4803";
+        assert_eq!(RegProducer::read(&hex::decode(expected).unwrap(), &mut 0).unwrap(), regproducer);
+        assert_action_data(regproducer, "regproducer", expected);
+        assert_action_data(UnregProd { producer: parse("bp1") }, "unregprod", "000000000000423d");
+        assert_action_data(ClaimRewards { owner: parse("bp1") }, "claimrewards", "000000000000423d");
+    }
+
+    #[test]
+    fn deploy_actions_should_work() {
+        assert_action_data(
+            SetCode { account: parse("alice"), vmtype: 0, vmversion: 0, code: hex::decode("0061736d01000000").unwrap() },
+            "setcode",
+            "0000000000855c340000080061736d01000000",
+        );
+        assert_action_data(
+            SetAbi { account: parse("alice"), abi: hex::decode("0e656f73696f3a3a6162692f312e3100").unwrap() },
+            "setabi",
+            "0000000000855c34100e656f73696f3a3a6162692f312e3100",
+        );
+    }
+
+    #[test]
+    fn linkauth_json_should_use_type_field() {
+        let json = r#"{"account":"alice","code":"eosio.token","type":"transfer","requirement":"custom"}"#;
+        let linkauth: LinkAuth = serde_json::from_str(json).unwrap();
+        assert_eq!(linkauth.type_, parse::<ActionName>("transfer"));
+        assert_eq!(serde_json::to_string(&linkauth).unwrap(), json);
+    }
+}
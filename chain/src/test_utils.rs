@@ -0,0 +1,83 @@
+//! Producers, keys and block production shared by the tests.
+use alloc::vec::Vec;
+use core::str::FromStr;
+use keys::secret::SecretKey;
+use crate::{
+    AccountName, BlockHeader, BlockHeaderState, BlockTimestamp, Checksum256, ProducerKey,
+    ProducerSchedule, PublicKey, SignedBlockHeader
+};
+
+/// Test keys, the one of `PRODUCERS[i]` is `WIFS[i]`.
+pub const WIFS: [&str; 4] = [
+    "5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3",
+    "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4",
+    "5KJVA9P4xsiRC3zPy1KPa3GA6ffvmyZSxhKPbE924YJphvSCG4F",
+    "5KhK251Mp2xqgQgpgsvScvk4gh8tVyettj2Govj4EkhvpnGLUqo",
+];
+pub const PRODUCERS: [&str; 4] = ["eosio", "proda", "prodb", "prodc"];
+
+pub fn secret_keys() -> Vec<SecretKey> {
+    WIFS.iter().map(|wif| SecretKey::from_wif(wif).unwrap()).collect()
+}
+
+pub fn public_key(wif: &str) -> PublicKey {
+    keys::public::PublicKey::from(&SecretKey::from_wif(wif).unwrap()).into()
+}
+
+pub fn producer(name: &str) -> AccountName {
+    AccountName::from_str(name).unwrap()
+}
+
+/// The key of one of `PRODUCERS`.
+pub fn producer_secret_key(producer: AccountName) -> SecretKey {
+    let pos = PRODUCERS.iter().position(|name| AccountName::from_str(name).unwrap() == producer).unwrap();
+    SecretKey::from_wif(WIFS[pos]).unwrap()
+}
+
+pub fn schedule(version: u32, names: &[&str]) -> ProducerSchedule {
+    ProducerSchedule::new(version, names.iter().map(|name| {
+        let producer_name = producer(name);
+        let pk: PublicKey = keys::public::PublicKey::from(&producer_secret_key(producer_name)).into();
+        ProducerKey { producer_name, block_signing_key: pk }
+    }).collect())
+}
+
+/// A genesis block in slot 1000 scheduling `names`.
+pub fn genesis(names: &[&str]) -> BlockHeaderState {
+    let header = BlockHeader {
+        timestamp: BlockTimestamp(1_000),
+        action_mroot: Checksum256::hash_from_slice(b"chain id"),
+        ..Default::default()
+    };
+    BlockHeaderState::genesis(header, schedule(0, names)).unwrap()
+}
+
+/// Sign `header` with the key of its producer, `header` must apply to `state`.
+pub fn sign(state: &BlockHeaderState, mut header: SignedBlockHeader) -> SignedBlockHeader {
+    let unsigned = state.next_unverified(&header).unwrap();
+    let sk = producer_secret_key(header.block_header.producer);
+    header.producer_signature = sk.sign_hash(unsigned.sig_digest().unwrap().as_bytes()).unwrap().into();
+    header
+}
+
+/// The header of the block after `state` in slot `timestamp`, signed by the scheduled
+/// producer which confirms every block since its last one like nodeos producers do.
+pub fn produce(
+    state: &BlockHeaderState,
+    timestamp: BlockTimestamp,
+    new_producers: Option<ProducerSchedule>,
+) -> SignedBlockHeader {
+    let producer = state.get_scheduled_producer(timestamp).unwrap().producer_name;
+    let confirmed = state.producer_to_last_produced.get(&producer)
+        .map_or(0, |last| (state.block_num - last) as u16);
+    let header = BlockHeader {
+        timestamp,
+        producer,
+        confirmed,
+        previous: state.id,
+        schedule_version: state.active_schedule.version,
+        new_producers,
+        ..Default::default()
+    };
+    sign(state, SignedBlockHeader::new(header, Default::default()))
+}
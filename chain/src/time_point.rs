@@ -34,7 +34,7 @@ impl TimePoint {
     }
 
     pub fn from_unix_nano_seconds(nano_sec: i64) -> Self {
-        Self(nano_sec)
+        Self(nano_sec / 1_000)
     }
 
     #[cfg(feature = "std")]
@@ -85,7 +85,7 @@ impl TryFrom<TimePoint> for u64 {
 #[cfg(feature = "std")]
 impl std::fmt::Display for TimePoint {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let dt = Utc.timestamp_nanos(self.time_since_epoch());
+        let dt = Utc.timestamp_millis(self.time_since_epoch().div_euclid(1_000));
         write!(f, "{}", dt.to_rfc3339_opts(SecondsFormat::Millis, true))
     }
 }
@@ -286,7 +286,7 @@ impl core::fmt::Display for TransactionHeader {
 }
 
 #[cfg_attr(feature = "std", derive(Deserialize, Serialize))]
-#[derive(NumBytes, Write, Read, Debug, Clone, Default, SerializeData)]
+#[derive(NumBytes, Write, Read, Debug, Clone, Default, PartialEq, SerializeData)]
 #[eosio_core_root_path = "crate"]
 pub struct Transaction {
     pub header: TransactionHeader,
@@ -8,6 +8,7 @@ use alloc::vec;
 use byteorder::{ByteOrder, LittleEndian};
 use bitcoin_hashes::{sha256d, Hash};
 use core::{fmt, str, slice, iter};
+use zeroize::{Zeroize, Zeroizing};
 
 /// An error that might occur during base58 decoding
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -93,6 +94,14 @@ impl<T: Default + Copy> SmallVec<T> {
     }
 }
 
+impl<T: Zeroize> Zeroize for SmallVec<T> {
+    fn zeroize(&mut self) {
+        self.stack.iter_mut().for_each(Zeroize::zeroize);
+        self.heap.zeroize();
+        self.len = 0;
+    }
+}
+
 static BASE58_CHARS: &'static [u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
 
 static BASE58_DIGITS: [Option<u8>; 128] = [
@@ -117,7 +126,7 @@ static BASE58_DIGITS: [Option<u8>; 128] = [
 /// Decode base58-encoded string into a byte vector
 pub fn from(data: &str) -> Result<Vec<u8>, Error> {
     // 11/15 is just over log_256(58)
-    let mut scratch = vec![0u8; 1 + data.len() * 11 / 15];
+    let mut scratch = Zeroizing::new(vec![0u8; 1 + data.len() * 11 / 15]);
     // Build in base 256
     for d58 in data.bytes() {
         // Compute "X = X * 58 + next_digit" in base 256
@@ -141,7 +150,7 @@ pub fn from(data: &str) -> Result<Vec<u8>, Error> {
         .map(|_| 0)
         .collect();
     // Copy rest of string
-    ret.extend(scratch.into_iter().skip_while(|&x| x == 0));
+    ret.extend(scratch.iter().skip_while(|&&x| x == 0));
     Ok(ret)
 }
 
@@ -165,9 +174,10 @@ pub fn from_check(data: &str) -> Result<Vec<u8>, Error> {
 fn format_iter<I, W>(writer: &mut W, data: I) -> Result<(), fmt::Error>
     where
         I: Iterator<Item=u8> + Clone,
-        W: fmt::Write
+        W: fmt::Write + ?Sized
 {
-    let mut ret = SmallVec::new();
+    // The digits of a WIF are as secret as the key, wipe them whatever the outcome
+    let mut ret = Zeroizing::new(SmallVec::new());
 
     let mut leading_zero_count = 0;
     let mut leading_zeroes = true;
@@ -203,6 +213,59 @@ fn format_iter<I, W>(writer: &mut W, data: I) -> Result<(), fmt::Error>
     Ok(())
 }
 
+/// Encode a slice as base58 digits (0-57, most significant first) into `buf`
+/// without allocating, returns the number of digits written.
+pub(crate) fn encode_digits(data: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
+    let mut len = 0;
+    let mut leading_zero_count = 0;
+    let mut leading_zeroes = true;
+    // Build number in little endian with 0-58 in place of characters...
+    for &d256 in data {
+        let mut carry = d256 as usize;
+        if leading_zeroes && carry == 0 {
+            leading_zero_count += 1;
+        } else {
+            leading_zeroes = false;
+        }
+
+        for ch in buf[..len].iter_mut() {
+            let new_ch = *ch as usize * 256 + carry;
+            *ch = (new_ch % 58) as u8;
+            carry = new_ch / 58;
+        }
+        while carry > 0 {
+            if len == buf.len() {
+                return Err(Error::InvalidLength(data.len()));
+            }
+            buf[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+
+    for _ in 0..leading_zero_count {
+        if len == buf.len() {
+            return Err(Error::InvalidLength(data.len()));
+        }
+        buf[len] = 0;
+        len += 1;
+    }
+
+    // ... then reverse it
+    buf[..len].reverse();
+    Ok(len)
+}
+
+/// Look up the base58 digit of an ASCII character
+pub(crate) fn digit(ch: u8) -> Option<u8> {
+    BASE58_DIGITS.get(ch as usize).cloned().flatten()
+}
+
+/// Look up the ASCII character of a base58 digit
+pub(crate) fn char_of(digit: u8) -> char {
+    BASE58_CHARS[digit as usize] as char
+}
+
 fn encode_iter<I>(data: I) -> String
     where
         I: Iterator<Item=u8> + Clone,
@@ -229,9 +292,9 @@ pub fn check_encode_slice(data: &[u8]) -> String {
     )
 }
 
-/// Obtain a string with the base58check encoding of a slice
+/// Write the base58check encoding of a slice into `fmt` without an intermediate string
 /// (Tack the first 4 256-digits of the object's Bitcoin hash onto the end.)
-pub fn check_encode_slice_to_fmt(fmt: &mut fmt::Formatter, data: &[u8]) -> fmt::Result {
+pub fn check_encode_slice_to_fmt<W: fmt::Write + ?Sized>(fmt: &mut W, data: &[u8]) -> fmt::Result {
     let checksum = sha256d::Hash::hash(&data);
     let iter = data.iter()
         .cloned()
@@ -286,6 +349,17 @@ mod tests {
             Some(hex_decode("00f8917303bfa8ef24f292e8fa1419b20460ba064d").unwrap()))
     }
 
+    #[test]
+    fn test_base58_encode_digits() {
+        let mut buf = [0u8; 16];
+        let len = encode_digits(&[0, 13, 36], &mut buf).unwrap();
+        let s: String = buf[..len].iter().map(|d| char_of(*d)).collect();
+        assert_eq!(s, "1211");
+
+        let mut small = [0u8; 2];
+        assert!(encode_digits(&[0, 13, 36], &mut small).is_err());
+    }
+
     #[test]
     fn test_base58_roundtrip() {
         let s = "xprv9wTYmMFdV23N2TdNG573QoEsfRrWKQgWeibmLntzniatZvR9BmLnvSxqu53Kw1UmYPxLgboyZQaXwTCg8MSY3H2EU4pWcQDnRnrVA1xe8fs";
@@ -0,0 +1,83 @@
+use std::{env, process, thread};
+use std::time::Duration;
+use eos_keys::vanity::{CancelToken, KeyFormat, Matcher, Position, VanitySearch};
+
+const USAGE: &str = "Usage: eos-keys vanity [--suffix | --contains] [--ignore-case] [--k1] [--threads N] [--timeout SECS] PATTERN";
+
+fn exit_with_usage(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    value.and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| exit_with_usage(&format!("{} expects a number", flag)))
+}
+
+fn vanity(mut args: impl Iterator<Item=String>) {
+    let mut position = Position::Prefix;
+    let mut format = KeyFormat::Legacy;
+    let mut ignore_case = false;
+    let mut threads = None;
+    let mut timeout = None;
+    let mut pattern = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--suffix" => position = Position::Suffix,
+            "--contains" => position = Position::Contains,
+            "--ignore-case" => ignore_case = true,
+            "--k1" => format = KeyFormat::K1,
+            "--threads" => threads = Some(parse_number::<usize>("--threads", args.next())),
+            "--timeout" => timeout = Some(parse_number::<u64>("--timeout", args.next())),
+            _ if arg.starts_with("--") => exit_with_usage(&format!("unknown option {}", arg)),
+            _ if pattern.is_none() => pattern = Some(arg),
+            _ => exit_with_usage("only one pattern can be given"),
+        }
+    }
+
+    let pattern = pattern.unwrap_or_else(|| exit_with_usage("missing pattern"));
+    let matcher = Matcher::new(&pattern, position, format, ignore_case)
+        .unwrap_or_else(|e| exit_with_usage(&format!("invalid pattern: {}", e)));
+    eprintln!("searching {}... about {:.0} keys expected", format.prefix(), matcher.expected_attempts());
+
+    let mut search = VanitySearch::with_matcher(matcher);
+    if let Some(threads) = threads {
+        search = search.threads(threads);
+    }
+
+    let cancel = CancelToken::new();
+    if let Some(secs) = timeout {
+        let cancel = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(secs));
+            cancel.cancel();
+        });
+    }
+
+    let result = search.run(&cancel, |progress| {
+        eprintln!("{} keys in {}s ({:.0} keys/s)", progress.attempts, progress.elapsed.as_secs(), progress.rate());
+    });
+
+    match result {
+        Some(found) => {
+            eprintln!("found after {} keys in {:.1}s", found.attempts, found.elapsed.as_secs_f64());
+            println!("Public key: {}", format.format(&found.keypair.pk));
+            println!("Private key: {}", found.keypair.sk.expose_wif().as_str());
+        }
+        None => {
+            eprintln!("no match, search cancelled");
+            process::exit(1);
+        }
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("vanity") => vanity(args),
+        Some(cmd) => exit_with_usage(&format!("unknown command {}", cmd)),
+        None => exit_with_usage("missing command"),
+    }
+}
@@ -4,7 +4,7 @@ use crate::base58;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Error {
     /// Base58 encoding error
     Base58(base58::Error),
@@ -14,6 +14,10 @@ pub enum Error {
     Hash(bitcoin_hashes::error::Error),
     /// verify failed
     VerifyFailed,
+    /// Hex decoding error
+    Hex(hex::FromHexError),
+    /// EIP-55 mixed-case checksum of an EVM address mismatch
+    BadEvmChecksum,
 }
 
 impl fmt::Display for Error {
@@ -23,6 +27,8 @@ impl fmt::Display for Error {
             Error::Secp256k1(ref e) => f.write_str(&e.to_string()),
             Error::Hash(ref e) => f.write_str(&e.to_string()),
             Error::VerifyFailed => f.write_str("Verify failed"),
+            Error::Hex(ref e) => fmt::Display::fmt(e, f),
+            Error::BadEvmChecksum => f.write_str("EVM address checksum mismatch"),
         }
     }
 }
@@ -65,3 +71,9 @@ impl From<bitcoin_hashes::error::Error> for Error {
         Error::Hash(e)
     }
 }
+
+impl From<hex::FromHexError> for Error {
+    fn from(e: hex::FromHexError) -> Error {
+        Error::Hex(e)
+    }
+}
@@ -0,0 +1,306 @@
+//! EOS EVM interop
+//!
+//! An EVM account is controlled by the same secp256k1 key as a native account,
+//! its address is the last 20 bytes of `keccak256` over the uncompressed public
+//! key without the leading `0x04` tag.
+
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+use crate::error;
+use crate::hash;
+use crate::public::PublicKey;
+use crate::rlp;
+use crate::secret::SecretKey;
+
+/// The size (in bytes) of an EVM address
+pub const ADDRESS_SIZE: usize = 20;
+
+/// A 20 bytes EVM account address.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Address(pub [u8; ADDRESS_SIZE]);
+
+impl Address {
+    /// Derive the address controlled by a public key.
+    pub fn from_public_key(pk: &PublicKey) -> Self {
+        let hash = hash::keccak256(&pk.key.serialize()[1..]).take();
+        let mut address = [0u8; ADDRESS_SIZE];
+        address.copy_from_slice(&hash[32 - ADDRESS_SIZE..]);
+
+        Address(address)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; ADDRESS_SIZE] {
+        &self.0
+    }
+
+    /// Format the address as EIP-55 mixed-case checksummed hex, without `0x`.
+    fn checksum_hex(&self) -> [u8; ADDRESS_SIZE * 2] {
+        let mut lower = [0u8; ADDRESS_SIZE * 2];
+        hex::encode_to_slice(self.0, &mut lower).expect("buffer has the exact size; qed");
+        let hash = hash::keccak256(&lower).take();
+
+        let mut checksummed = lower;
+        for (i, ch) in checksummed.iter_mut().enumerate() {
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if nibble >= 8 {
+                ch.make_ascii_uppercase();
+            }
+        }
+        checksummed
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let checksummed = self.checksum_hex();
+        f.write_str("0x")?;
+        f.write_str(core::str::from_utf8(&checksummed).expect("hex is always valid utf8; qed"))
+    }
+}
+
+impl FromStr for Address {
+    type Err = error::Error;
+
+    /// Parse a hex address, `0x` is optional. Mixed-case input must carry a
+    /// valid EIP-55 checksum, all lower or all upper case input is accepted as is.
+    fn from_str(s: &str) -> crate::Result<Address> {
+        let s = s.strip_prefix("0x").unwrap_or(s);
+        let mut address = [0u8; ADDRESS_SIZE];
+        hex::decode_to_slice(s, &mut address)?;
+        let address = Address(address);
+
+        let has_lower = s.bytes().any(|b| b.is_ascii_lowercase());
+        let has_upper = s.bytes().any(|b| b.is_ascii_uppercase());
+        if has_lower && has_upper && address.checksum_hex()[..] != *s.as_bytes() {
+            return Err(error::Error::BadEvmChecksum);
+        }
+
+        Ok(address)
+    }
+}
+
+impl From<[u8; ADDRESS_SIZE]> for Address {
+    fn from(address: [u8; ADDRESS_SIZE]) -> Self {
+        Address(address)
+    }
+}
+
+impl From<&PublicKey> for Address {
+    fn from(pk: &PublicKey) -> Self {
+        Address::from_public_key(pk)
+    }
+}
+
+/// A legacy Ethereum transaction, signed either pre EIP-155 or with a chain id.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Transaction {
+    pub nonce: u64,
+    /// Gas price in wei
+    pub gas_price: u128,
+    pub gas_limit: u64,
+    /// The recipient, `None` creates a contract
+    pub to: Option<Address>,
+    /// Amount in wei
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl Transaction {
+    fn append_fields(&self, out: &mut Vec<u8>) {
+        rlp::append_uint(out, self.nonce.into());
+        rlp::append_uint(out, self.gas_price);
+        rlp::append_uint(out, self.gas_limit.into());
+        match self.to {
+            Some(ref to) => rlp::append_bytes(out, &to.0),
+            None => rlp::append_bytes(out, &[]),
+        }
+        rlp::append_uint(out, self.value);
+        rlp::append_bytes(out, &self.data);
+    }
+
+    /// The RLP payload that is hashed for signing, EIP-155 appends
+    /// `chain_id, 0, 0` to the six transaction fields.
+    pub fn signing_payload(&self, chain_id: Option<u64>) -> Vec<u8> {
+        let mut payload = Vec::new();
+        self.append_fields(&mut payload);
+        if let Some(chain_id) = chain_id {
+            rlp::append_uint(&mut payload, chain_id.into());
+            rlp::append_uint(&mut payload, 0);
+            rlp::append_uint(&mut payload, 0);
+        }
+        rlp::encode_list(&payload)
+    }
+
+    pub fn signing_hash(&self, chain_id: Option<u64>) -> [u8; 32] {
+        hash::keccak256(&self.signing_payload(chain_id)).take()
+    }
+
+    /// Sign the transaction, pass `None` as chain id for a pre EIP-155 signature.
+    pub fn sign(self, sk: &SecretKey, chain_id: Option<u64>) -> crate::Result<SignedTransaction> {
+        let signature = sk.sign_hash(&self.signing_hash(chain_id))?;
+        let rs = signature.sig.serialize();
+        let recovery_id = u64::from(signature.recv_id.serialize());
+        let v = match chain_id {
+            Some(chain_id) => chain_id * 2 + 35 + recovery_id,
+            None => 27 + recovery_id,
+        };
+
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&rs[..32]);
+        s.copy_from_slice(&rs[32..]);
+
+        Ok(SignedTransaction { transaction: self, v, r, s })
+    }
+}
+
+/// A signed legacy Ethereum transaction.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct SignedTransaction {
+    pub transaction: Transaction,
+    pub v: u64,
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+}
+
+impl SignedTransaction {
+    /// The chain id the transaction was signed for, `None` before EIP-155.
+    pub fn chain_id(&self) -> Option<u64> {
+        if self.v >= 35 {
+            Some((self.v - 35) / 2)
+        } else {
+            None
+        }
+    }
+
+    fn recovery_id(&self) -> u8 {
+        let id = match self.chain_id() {
+            Some(chain_id) => self.v - chain_id * 2 - 35,
+            None => self.v.wrapping_sub(27),
+        };
+        id as u8
+    }
+
+    /// The raw transaction, as accepted by `eth_sendRawTransaction` and `eosio.evm::pushtx`.
+    pub fn rlp_bytes(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        self.transaction.append_fields(&mut payload);
+        rlp::append_uint(&mut payload, self.v.into());
+        rlp::append_uint_bytes(&mut payload, &self.r);
+        rlp::append_uint_bytes(&mut payload, &self.s);
+        rlp::encode_list(&payload)
+    }
+
+    /// The transaction hash.
+    pub fn hash(&self) -> [u8; 32] {
+        hash::keccak256(&self.rlp_bytes()).take()
+    }
+
+    /// Recover the address that signed the transaction.
+    pub fn sender(&self) -> crate::Result<Address> {
+        let hash = self.transaction.signing_hash(self.chain_id());
+        let msg = secp256k1::Message::parse_slice(&hash)?;
+        let mut rs = [0u8; 64];
+        rs[..32].copy_from_slice(&self.r);
+        rs[32..].copy_from_slice(&self.s);
+        let sig = secp256k1::Signature::parse_slice(&rs)?;
+        let recovery_id = secp256k1::RecoveryId::parse(self.recovery_id())?;
+        let key = secp256k1::recover(&msg, &sig, &recovery_id)?;
+
+        Ok(Address::from_public_key(&PublicKey { compressed: true, key }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Address, Transaction};
+    use core::str::FromStr;
+    use crate::error;
+    use crate::public::PublicKey;
+    use crate::secret::SecretKey;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn address_from_public_key_should_work() {
+        let sk = SecretKey::from_hex("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let pk = PublicKey::from(&sk);
+        assert_eq!(pk.to_string(), "EOS5UwhML5Kv6TbBXPyTKUYtVx3CVKYfwA4izux8XvvdtVKAEmBAY");
+        assert_eq!(Address::from(&pk).to_string(), "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23");
+
+        let pk = PublicKey::from_str("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV").unwrap();
+        assert_eq!(pk.to_evm_address().to_string(), "0x7403B5f4548cCb85d206B0C9a9059c49ef38E860");
+    }
+
+    #[test]
+    fn address_from_str_should_work() {
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let address = Address::from_str(checksummed).unwrap();
+        assert_eq!(address.to_string(), checksummed);
+        assert_eq!(Address::from_str("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap(), address);
+        assert_eq!(Address::from_str("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap(), address);
+    }
+
+    #[test]
+    fn address_from_str_should_error() {
+        assert_eq!(
+            Address::from_str("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"),
+            Err(error::Error::BadEvmChecksum),
+        );
+        assert_eq!(
+            Address::from_str("0x5aaeb6053f3e94c9b9a09f33669435e7ef1bea"),
+            Err(error::Error::Hex(hex::FromHexError::InvalidStringLength)),
+        );
+    }
+
+    fn eip155_transaction() -> Transaction {
+        Transaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21000,
+            to: Some(Address::from_str("0x3535353535353535353535353535353535353535").unwrap()),
+            value: 1_000_000_000_000_000_000,
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn transaction_signing_hash_should_work() {
+        let trx = eip155_transaction();
+        assert_eq!(
+            hex::encode(trx.signing_payload(Some(1))),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+        assert_eq!(
+            hex::encode(trx.signing_hash(Some(1))),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+    }
+
+    #[test]
+    fn transaction_sign_should_work() {
+        // https://eips.ethereum.org/EIPS/eip-155
+        let sk = SecretKey::from_hex("4646464646464646464646464646464646464646464646464646464646464646").unwrap();
+        let signed = eip155_transaction().sign(&sk, Some(1)).unwrap();
+        assert_eq!(signed.v, 37);
+        assert_eq!(signed.chain_id(), Some(1));
+        assert_eq!(
+            hex::encode(signed.rlp_bytes()),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+        assert_eq!(
+            hex::encode(signed.hash()),
+            "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+        );
+        assert_eq!(signed.sender().unwrap().to_string().to_lowercase(), "0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+    }
+
+    #[test]
+    fn transaction_sign_without_chain_id_should_work() {
+        let sk = SecretKey::from_hex("4646464646464646464646464646464646464646464646464646464646464646").unwrap();
+        let signed = eip155_transaction().sign(&sk, None).unwrap();
+        assert!(signed.v == 27 || signed.v == 28);
+        assert_eq!(signed.chain_id(), None);
+        assert_eq!(signed.sender().unwrap(), Address::from(&PublicKey::from(&sk)));
+    }
+}
@@ -5,6 +5,7 @@ use bitcoin_hashes::{ripemd160, Hash as HashTrait, HashEngine};
 use core::{ops, cmp, str};
 use core::hash::{Hash, Hasher};
 use hex::{FromHex, FromHexError};
+use tiny_keccak::{Hasher as KeccakHasher, Keccak};
 
 macro_rules! impl_hash {
     ($name: ident, $size: expr) => {
@@ -186,3 +187,12 @@ pub fn ripemd160(msg: &[u8]) -> H160 {
     engine.input(msg);
     ripemd160::Hash::from_engine(engine).into_inner().into()
 }
+
+/// Computes Keccak-256 hash as used by Ethereum, which differs from SHA3-256 in padding
+pub fn keccak256(msg: &[u8]) -> H256 {
+    let mut output = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(msg);
+    hasher.finalize(&mut output);
+    output.into()
+}
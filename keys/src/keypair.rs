@@ -1,8 +1,11 @@
+use alloc::vec;
+use alloc::vec::Vec;
 use crate::constant::*;
 use crate::public::PublicKey;
 use crate::secret::SecretKey;
 use crate::signature::Signature;
 use rand::Rng;
+use zeroize::Zeroizing;
 
 
 /// A secp256k1 keypair.
@@ -31,9 +34,9 @@ impl Keypair {
         Ok(Keypair { sk, pk })
     }
 
-    /// Convert this keypair to bytes.
-    pub fn to_bytes(&self) -> [u8; KEYPAIR_LENGTH] {
-        let mut bytes: [u8; KEYPAIR_LENGTH] = [0u8; KEYPAIR_LENGTH];
+    /// Convert this keypair to bytes, the buffer is wiped when dropped.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut bytes = Zeroizing::new(vec![0u8; KEYPAIR_LENGTH]);
 
         bytes[..SECRET_KEY_SIZE].copy_from_slice(self.sk.to_bytes().as_slice());
         bytes[SECRET_KEY_SIZE..].copy_from_slice(self.pk.to_bytes().as_slice());
@@ -96,4 +99,14 @@ mod tests {
         let verify = keypair.verify(&message, &sig.unwrap());
         assert!(verify.is_ok());
     }
+
+    #[test]
+    fn keypair_to_bytes_should_work() {
+        let wif = "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4";
+        let keypair = Keypair::from_secret_wif(wif).unwrap();
+        let bytes = keypair.to_bytes();
+        assert_eq!(bytes.len(), super::KEYPAIR_LENGTH);
+        assert_eq!(&bytes[..super::SECRET_KEY_SIZE], keypair.sk.to_bytes().as_slice());
+        assert_eq!(&bytes[super::SECRET_KEY_SIZE..], keypair.pk.to_bytes().as_slice());
+    }
 }
@@ -7,6 +7,10 @@ pub mod public;
 pub mod secret;
 pub mod signature;
 pub mod error;
+pub mod evm;
+pub mod rlp;
+#[cfg(feature = "std")]
+pub mod vanity;
 
 mod constant;
 mod hash;
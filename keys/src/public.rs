@@ -7,6 +7,7 @@ use crate::constant::*;
 use crate::{error, hash};
 use crate::secret::SecretKey;
 use crate::base58;
+use crate::evm::Address;
 use crate::signature::Signature;
 
 /// A Secp256k1 public key
@@ -39,6 +40,25 @@ impl PublicKey {
         format!("EOS{}", base58::encode_slice(&public_key))
     }
 
+    /// Serialize the public key to `PUB_K1_` format string
+    pub fn to_k1_fmt(&self) -> String {
+        let data = self.key.serialize_compressed();
+        let mut checksum_data = [0u8; PUBLIC_KEY_SIZE + 2];
+        checksum_data[..PUBLIC_KEY_SIZE].copy_from_slice(&data);
+        checksum_data[PUBLIC_KEY_SIZE..].copy_from_slice(b"K1");
+        let h160 = hash::ripemd160(&checksum_data);
+        let mut public_key = [0u8; PUBLIC_KEY_WITH_CHECKSUM_SIZE];
+        public_key[..PUBLIC_KEY_SIZE].copy_from_slice(&data);
+        public_key[PUBLIC_KEY_SIZE..].copy_from_slice(&h160.take()[..PUBLIC_KEY_CHECKSUM_SIZE]);
+
+        format!("PUB_K1_{}", base58::encode_slice(&public_key))
+    }
+
+    /// Derive the EOS EVM (Ethereum) address controlled by this key.
+    pub fn to_evm_address(&self) -> Address {
+        Address::from_public_key(self)
+    }
+
     /// Verify a signature on a message with public key.
     pub fn verify(&self, message_slice: &[u8], signature: &Signature) -> crate::Result<()> {
         let msg_hash = sha256::Hash::hash(&message_slice);
@@ -54,6 +74,14 @@ impl PublicKey {
         }
     }
 
+    /// Recover the public key that produced a signature on a hash.
+    pub fn recover_hash(hash: &[u8], signature: &Signature) -> crate::Result<PublicKey> {
+        let msg = secp256k1::Message::parse_slice(hash)?;
+        let key = secp256k1::recover(&msg, &signature.sig, &signature.recv_id)?;
+
+        Ok(PublicKey { compressed: true, key })
+    }
+
     /// Deserialize a public key from a slice
     pub fn from_slice(data: &[u8]) -> crate::Result<PublicKey> {
         let compressed: bool = match data.len() {
@@ -119,6 +147,7 @@ impl<'a> From<&'a SecretKey> for PublicKey {
 #[cfg(test)]
 mod test {
     use super::PublicKey;
+    use bitcoin_hashes::{sha256, Hash as HashTrait};
     use core::str::FromStr;
     use crate::error;
     use crate::signature::Signature;
@@ -133,6 +162,12 @@ mod test {
         assert_eq!(pk.unwrap().to_string(), pk_str);
     }
 
+    #[test]
+    fn pk_to_k1_fmt_should_work() {
+        let pk = PublicKey::from_str("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV").unwrap();
+        assert_eq!(pk.to_k1_fmt(), "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63");
+    }
+
     #[test]
     fn pk_from_str_should_error() {
         let pk_str = "8FdQ4gt16pFcSiXAYCcHnkHTS2nNLFWGZXW5sioAdvQuMxKhAm";
@@ -169,4 +204,19 @@ mod test {
         assert!(vfy.is_err());
         assert_eq!(vfy, Err(crate::error::Error::VerifyFailed));
     }
+
+    #[test]
+    fn pk_recover_hash_should_work() {
+        let pk_str = "EOS86jwjSu9YkD4JDJ7nGK1Rx2SmvNMQ3XiKrvFndABzLDPwk1ZHx";
+        let sig_str = "SIG_K1_KomV6FEHKdtZxGDwhwSubEAcJ7VhtUQpEt5P6iDz33ic936aSXx87B2L56C8JLQkqNpp1W8ZXjrKiLHUEB4LCGeXvbtVuR";
+        let sig = Signature::from_str(sig_str).unwrap();
+
+        let hash = sha256::Hash::hash("hello".as_bytes());
+        let pk = PublicKey::recover_hash(&hash, &sig);
+        assert!(pk.is_ok());
+        assert_eq!(pk.unwrap().to_string(), pk_str);
+
+        let hash = sha256::Hash::hash("world".as_bytes());
+        assert_ne!(PublicKey::recover_hash(&hash, &sig).unwrap().to_string(), pk_str);
+    }
 }
@@ -0,0 +1,95 @@
+//! Recursive Length Prefix encoding
+//!
+//! Only the encoder needed to build and sign Ethereum transactions.
+//! <https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/>
+
+use alloc::vec::Vec;
+
+const STRING_OFFSET: u8 = 0x80;
+const LIST_OFFSET: u8 = 0xc0;
+
+fn append_length(out: &mut Vec<u8>, len: usize, offset: u8) {
+    if len < 56 {
+        out.push(offset + len as u8);
+    } else {
+        let bytes = (len as u64).to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(offset + 55 + (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+/// Append a byte string.
+pub fn append_bytes(out: &mut Vec<u8>, data: &[u8]) {
+    if data.len() == 1 && data[0] < STRING_OFFSET {
+        out.push(data[0]);
+    } else {
+        append_length(out, data.len(), STRING_OFFSET);
+        out.extend_from_slice(data);
+    }
+}
+
+/// Append a big endian unsigned integer, leading zeroes are stripped.
+pub fn append_uint_bytes(out: &mut Vec<u8>, be_bytes: &[u8]) {
+    let skip = be_bytes.iter().take_while(|b| **b == 0).count();
+    append_bytes(out, &be_bytes[skip..]);
+}
+
+/// Append an unsigned integer, zero is encoded as the empty string.
+pub fn append_uint(out: &mut Vec<u8>, value: u128) {
+    append_uint_bytes(out, &value.to_be_bytes());
+}
+
+/// Wrap already encoded items into a list.
+pub fn encode_list(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 9);
+    append_length(&mut out, payload.len(), LIST_OFFSET);
+    out.extend_from_slice(payload);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn rlp_bytes_should_work() {
+        let encode = |data: &[u8]| {
+            let mut out = vec![];
+            append_bytes(&mut out, data);
+            out
+        };
+        assert_eq!(encode(b""), vec![0x80]);
+        assert_eq!(encode(b"\x0f"), vec![0x0f]);
+        assert_eq!(encode(b"\x80"), vec![0x81, 0x80]);
+        assert_eq!(encode(b"dog"), vec![0x83, b'd', b'o', b'g']);
+
+        let long = [b'a'; 56];
+        let encoded = encode(&long);
+        assert_eq!(&encoded[..2], &[0xb8, 56]);
+        assert_eq!(&encoded[2..], &long[..]);
+    }
+
+    #[test]
+    fn rlp_uint_should_work() {
+        let encode = |value: u128| {
+            let mut out = vec![];
+            append_uint(&mut out, value);
+            out
+        };
+        assert_eq!(encode(0), vec![0x80]);
+        assert_eq!(encode(15), vec![0x0f]);
+        assert_eq!(encode(1024), vec![0x82, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn rlp_list_should_work() {
+        assert_eq!(encode_list(&[]), vec![0xc0]);
+
+        let mut payload = vec![];
+        append_bytes(&mut payload, b"cat");
+        append_bytes(&mut payload, b"dog");
+        assert_eq!(encode_list(&payload), vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']);
+    }
+}
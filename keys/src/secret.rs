@@ -2,17 +2,24 @@ use alloc::vec::Vec;
 use alloc::vec;
 use alloc::string::String;
 use bitcoin_hashes::{sha256, Hash as HashTrait};
-use core::fmt::{self, Write};
+use core::fmt;
 use core::str::FromStr;
+use crate::constant::*;
 use crate::error;
 use crate::network::Network;
 use crate::base58;
 use crate::network::Network::Mainnet;
 use crate::signature::Signature;
 use rand::Rng;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
 
 /// A Secp256k1 private key
-#[derive(Clone, PartialEq, Eq)]
+///
+/// The scalar is overwritten when the key is dropped, and every buffer handed out
+/// by this type is wrapped in `Zeroizing`. `Display` and `Debug` never print the
+/// key; call `expose_wif` when the WIF string is really needed.
+#[derive(Clone)]
 pub struct SecretKey {
     /// Whether this private key should be serialized as compressed
     pub compressed: bool,
@@ -32,41 +39,44 @@ impl SecretKey {
         }
     }
 
-    /// Serialize the private key to bytes
-    pub fn to_bytes(&self) -> Vec<u8> {
-        self.key.serialize().to_vec()
+    /// Serialize the private key to bytes, the buffer is wiped when dropped.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.key.serialize().to_vec())
     }
 
     /// Format the private key to WIF format.
     pub fn fmt_wif(&self, fmt: &mut dyn fmt::Write) -> fmt::Result {
-        let mut ret = [0; 34];
+        let mut ret = Zeroizing::new([0u8; 34]);
         ret[0] = match self.network {
             Network::Mainnet => 128,
             Network::Testnet => 239,
         };
-        ret[1..33].copy_from_slice(&self.key.serialize());
-        let privkey = if self.compressed {
+        ret[1..33].copy_from_slice(&*Zeroizing::new(self.key.serialize()));
+        if self.compressed {
             ret[33] = 1;
-            base58::check_encode_slice(&ret[..])
+            base58::check_encode_slice_to_fmt(fmt, &ret[..])
         } else {
-            base58::check_encode_slice(&ret[..33])
-        };
+            base58::check_encode_slice_to_fmt(fmt, &ret[..33])
+        }
+    }
 
-        fmt.write_str(&privkey)
+    /// Get WIF encoding of this private key, the string is wiped when dropped.
+    pub fn expose_wif(&self) -> Zeroizing<String> {
+        let mut buf = Zeroizing::new(String::with_capacity(52));
+        self.fmt_wif(&mut *buf).expect("writing into string shouldn't fail");
+
+        buf
     }
 
     /// Get WIF encoding of this private key.
+    #[deprecated(note = "the returned string is not wiped, use `expose_wif` instead")]
     pub fn to_wif(&self) -> Result<String, core::fmt::Error> {
-        let mut buf = String::new();
-        buf.write_fmt(format_args!("{}", self))?;
-        buf.shrink_to_fit();
-
-        Ok(buf)
+        Ok(String::from(self.expose_wif().as_str()))
     }
 
     /// Parse WIF encoded private key.
     pub fn from_wif(wif: &str) -> crate::Result<SecretKey> {
-        let data = base58::from_check(wif)?;
+        let data = Zeroizing::new(base58::from_check(wif)?);
 
         let compressed = match data.len() {
             33 => false,
@@ -87,6 +97,29 @@ impl SecretKey {
         })
     }
 
+    /// Parse a raw 32 bytes private key in hex, as exported by Ethereum wallets.
+    /// The `0x` prefix is optional.
+    pub fn from_hex(hex_str: &str) -> crate::Result<SecretKey> {
+        let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+        let mut data = Zeroizing::new([0u8; SECRET_KEY_SIZE]);
+        hex::decode_to_slice(hex_str, &mut data[..])?;
+
+        Ok(SecretKey {
+            compressed: false,
+            network: Mainnet,
+            key: secp256k1::SecretKey::parse_slice(&data[..])?,
+        })
+    }
+
+    /// Get the raw private key as lowercase hex without `0x`, the string is wiped when dropped.
+    pub fn expose_hex(&self) -> Zeroizing<String> {
+        let data = Zeroizing::new(self.key.serialize());
+        let mut buf = Zeroizing::new([0u8; SECRET_KEY_SIZE * 2]);
+        hex::encode_to_slice(&data[..], &mut buf[..]).expect("buffer has the exact size; qed");
+
+        Zeroizing::new(String::from_utf8_lossy(&buf[..]).into_owned())
+    }
+
     /// Deserialize a secret key from a slice
     pub fn from_slice(data: &[u8]) -> crate::Result<SecretKey> {
         let compressed: bool = match data.len() {
@@ -120,9 +153,43 @@ impl SecretKey {
     }
 }
 
+/// Convert a WIF private key into the raw hex form used by Ethereum wallets.
+pub fn wif_to_hex(wif: &str) -> crate::Result<Zeroizing<String>> {
+    Ok(SecretKey::from_wif(wif)?.expose_hex())
+}
+
+/// Convert a raw hex private key into WIF.
+pub fn hex_to_wif(hex_str: &str) -> crate::Result<Zeroizing<String>> {
+    Ok(SecretKey::from_hex(hex_str)?.expose_wif())
+}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        let key = Zeroizing::new(self.key.serialize());
+        let other_key = Zeroizing::new(other.key.serialize());
+
+        self.compressed == other.compressed
+            && self.network == other.network
+            && bool::from(key[..].ct_eq(&other_key[..]))
+    }
+}
+
+impl Eq for SecretKey {}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        // Don't rely on the secp256k1 dependency clearing its scalar, replace it with
+        // the fixed default key the same way `Zeroizing` wipes its content.
+        unsafe {
+            core::ptr::write_volatile(&mut self.key, secp256k1::SecretKey::default());
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
 impl fmt::Display for SecretKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.fmt_wif(f)
+        write!(f, "[private key data]")
     }
 }
 
@@ -141,8 +208,9 @@ impl FromStr for SecretKey {
 
 #[cfg(test)]
 mod test {
-    use super::SecretKey;
+    use super::{SecretKey, hex_to_wif, wif_to_hex};
     use crate::public::PublicKey;
+    use alloc::format;
     use alloc::string::ToString;
     #[cfg(feature = "std")]
     use rand::thread_rng;
@@ -174,4 +242,51 @@ mod test {
         assert!(sig.is_canonical());
         assert_eq!(sig.to_string(), "SIG_K1_KL1utX4aFNsSfEWVGtqcLAyhV5juPxhGNB7vGTUGKiWeEgxeXhxpuAPg44t7LeaBQPr5qf3Md7VZtEDZHtsfAwr95rpjGa");
     }
+
+    #[test]
+    fn sk_display_should_be_redacted() {
+        let wif = "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4";
+        let sk = SecretKey::from_wif(wif).unwrap();
+        assert_eq!(sk.to_string(), "[private key data]");
+        assert_eq!(format!("{:?}", sk), "[private key data]");
+    }
+
+    #[test]
+    fn sk_expose_wif_should_work() {
+        let wif = "5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4";
+        let sk = SecretKey::from_wif(wif).unwrap();
+        assert_eq!(sk.expose_wif().as_str(), wif);
+    }
+
+    #[test]
+    fn sk_eq_should_work() {
+        let sk1 = SecretKey::from_wif("5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4").unwrap();
+        let sk2 = SecretKey::from_wif("5HrBLKfeEdqH9KLMv1daHLVjrXV3DGVERAkN5cdSSc58bzqqfT4").unwrap();
+        let sk3 = SecretKey::from_wif("5KJVA9P4xsiRC3zPy1KPa3GA6ffvmyZSxhKPbE924YJphvSCG4F").unwrap();
+        assert_eq!(sk1, sk2);
+        assert_ne!(sk1, sk3);
+    }
+
+    #[test]
+    fn sk_hex_should_work() {
+        let hex = "d2653ff7cbb2d8ff129ac27ef5781ce68b2558c41a74af1f2ddca635cbeef07d";
+        let sk = SecretKey::from_hex(hex).unwrap();
+        assert_eq!(sk.expose_wif().as_str(), "5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3");
+        assert_eq!(sk.expose_hex().as_str(), hex);
+        assert_eq!(SecretKey::from_hex(&format!("0x{}", hex)).unwrap(), sk);
+
+        assert!(SecretKey::from_hex("d2653ff7cbb2d8ff129ac27ef5781ce68b2558c4").is_err());
+        assert!(SecretKey::from_hex("zz653ff7cbb2d8ff129ac27ef5781ce68b2558c41a74af1f2ddca635cbeef07d").is_err());
+        // zero is not a valid secp256k1 secret key
+        assert!(SecretKey::from_hex(&"0".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn wif_hex_conversion_should_work() {
+        let wif = "5JPmkQ5ytbHhDGrqLcCivaDaAvtfLAVHe4EBRoFTr82kew9qspy";
+        let hex = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+        assert_eq!(wif_to_hex(wif).unwrap().as_str(), hex);
+        assert_eq!(hex_to_wif(hex).unwrap().as_str(), wif);
+        assert_eq!(hex_to_wif(&format!("0x{}", hex)).unwrap().as_str(), wif);
+    }
 }
@@ -0,0 +1,396 @@
+//! Vanity public key search
+//!
+//! Keys are drawn with `Keypair::generate` on several threads until the base58
+//! body of the public key (the part after `EOS` or `PUB_K1_`) matches a pattern.
+//! Candidates are encoded into a fixed digit buffer and compared digit by digit,
+//! so no string is formatted until a match is found.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::base58;
+use crate::constant::*;
+use crate::hash;
+use crate::keypair::Keypair;
+use crate::public::PublicKey;
+
+/// The number of base58 digits of a serialized public key with checksum.
+const KEY_DIGITS: usize = 50;
+
+/// How many attempts a worker makes before publishing its counter.
+const REPORT_BATCH: u64 = 256;
+
+/// Where the pattern has to appear in the base58 body of the public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// Right after the `EOS`/`PUB_K1_` prefix
+    Prefix,
+    /// At the end of the key, the last characters cover the checksum
+    Suffix,
+    /// Anywhere in the key
+    Contains,
+}
+
+/// The public key string format the pattern is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// `EOS...`, checksum is `ripemd160(key)`
+    Legacy,
+    /// `PUB_K1_...`, checksum is `ripemd160(key || "K1")`
+    K1,
+}
+
+impl KeyFormat {
+    /// The prefix placed before the base58 body.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            KeyFormat::Legacy => "EOS",
+            KeyFormat::K1 => "PUB_K1_",
+        }
+    }
+
+    /// Format a public key in this format.
+    pub fn format(self, pk: &PublicKey) -> String {
+        match self {
+            KeyFormat::Legacy => pk.to_eos_fmt(),
+            KeyFormat::K1 => pk.to_k1_fmt(),
+        }
+    }
+
+    fn serialize(self, pk: &PublicKey) -> [u8; PUBLIC_KEY_WITH_CHECKSUM_SIZE] {
+        let data = pk.key.serialize_compressed();
+        let checksum = match self {
+            KeyFormat::Legacy => hash::ripemd160(&data),
+            KeyFormat::K1 => {
+                let mut checksum_data = [0u8; PUBLIC_KEY_SIZE + 2];
+                checksum_data[..PUBLIC_KEY_SIZE].copy_from_slice(&data);
+                checksum_data[PUBLIC_KEY_SIZE..].copy_from_slice(b"K1");
+                hash::ripemd160(&checksum_data)
+            }
+        };
+
+        let mut public_key = [0u8; PUBLIC_KEY_WITH_CHECKSUM_SIZE];
+        public_key[..PUBLIC_KEY_SIZE].copy_from_slice(&data);
+        public_key[PUBLIC_KEY_SIZE..].copy_from_slice(&checksum.take()[..PUBLIC_KEY_CHECKSUM_SIZE]);
+        public_key
+    }
+}
+
+/// A compiled pattern, each character is a bitmask of the accepted base58 digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matcher {
+    masks: Vec<u64>,
+    position: Position,
+    format: KeyFormat,
+}
+
+impl Matcher {
+    /// Compile `pattern`, fails if it has characters outside the base58 alphabet
+    /// or if a prefix can never start a compressed secp256k1 key.
+    pub fn new(pattern: &str, position: Position, format: KeyFormat, ignore_case: bool) -> crate::Result<Self> {
+        if pattern.is_empty() || pattern.len() > KEY_DIGITS {
+            return Err(base58::Error::InvalidLength(pattern.len()).into());
+        }
+
+        let mut masks = Vec::with_capacity(pattern.len());
+        for ch in pattern.bytes() {
+            let mut mask = 0u64;
+            let variants = if ignore_case {
+                [ch.to_ascii_lowercase(), ch.to_ascii_uppercase()]
+            } else {
+                [ch, ch]
+            };
+            for variant in variants.iter() {
+                if let Some(d) = base58::digit(*variant) {
+                    mask |= 1u64 << d;
+                }
+            }
+            if mask == 0 {
+                return Err(base58::Error::BadByte(ch).into());
+            }
+            masks.push(mask);
+        }
+
+        if position == Position::Prefix {
+            // compressed keys start with 0x02 or 0x03, which bounds the leading digit
+            let mut lowest = [0u8; PUBLIC_KEY_WITH_CHECKSUM_SIZE];
+            lowest[0] = 0x02;
+            let mut highest = [0xffu8; PUBLIC_KEY_WITH_CHECKSUM_SIZE];
+            highest[0] = 0x03;
+            let mut buf = [0u8; KEY_DIGITS];
+            base58::encode_digits(&lowest, &mut buf)?;
+            let first = buf[0];
+            base58::encode_digits(&highest, &mut buf)?;
+            let last = buf[0];
+            let feasible = (first..=last).fold(0u64, |acc, d| acc | 1u64 << d);
+            if masks[0] & feasible == 0 {
+                return Err(base58::Error::Other(format!(
+                    "a {} key never starts with '{}', the first character must be within '{}'..='{}'",
+                    format.prefix(), pattern.as_bytes()[0] as char, base58::char_of(first), base58::char_of(last),
+                )).into());
+            }
+        }
+
+        Ok(Matcher { masks, position, format })
+    }
+
+    /// The number of characters in the pattern.
+    pub fn pattern_len(&self) -> usize {
+        self.masks.len()
+    }
+
+    /// Check whether the public key matches the pattern.
+    pub fn is_match(&self, pk: &PublicKey) -> bool {
+        let mut buf = [0u8; KEY_DIGITS + 1];
+        let len = match base58::encode_digits(&self.format.serialize(pk), &mut buf) {
+            Ok(len) => len,
+            Err(_) => return false,
+        };
+        self.is_match_digits(&buf[..len])
+    }
+
+    fn is_match_digits(&self, digits: &[u8]) -> bool {
+        let n = self.masks.len();
+        if digits.len() < n {
+            return false;
+        }
+        let matches_at = |start: usize| {
+            self.masks.iter()
+                .zip(digits[start..start + n].iter())
+                .all(|(mask, d)| mask >> d & 1 == 1)
+        };
+
+        match self.position {
+            Position::Prefix => matches_at(0),
+            Position::Suffix => matches_at(digits.len() - n),
+            Position::Contains => (0..=digits.len() - n).any(matches_at),
+        }
+    }
+
+    /// Rough number of keys to try before a match, the leading digit of a key
+    /// is not uniformly distributed so this is only an estimate.
+    pub fn expected_attempts(&self) -> f64 {
+        let odds = self.masks.iter()
+            .fold(1f64, |acc, mask| acc * 58f64 / f64::from(mask.count_ones()));
+        match self.position {
+            Position::Prefix | Position::Suffix => odds,
+            Position::Contains => odds / (KEY_DIGITS - self.masks.len() + 1) as f64,
+        }
+    }
+}
+
+/// A handle to stop a running search from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask all workers to stop, `VanitySearch::run` then returns `None`.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Progress of a running search, reported at every interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Keys generated so far by all workers
+    pub attempts: u64,
+    /// Time since the search started
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Keys generated per second.
+    pub fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0f64 {
+            self.attempts as f64 / secs
+        } else {
+            0f64
+        }
+    }
+}
+
+/// The keypair found by a search.
+#[derive(Clone)]
+pub struct VanityMatch {
+    pub keypair: Keypair,
+    /// Keys generated by all workers until the match
+    pub attempts: u64,
+    pub elapsed: Duration,
+}
+
+/// A multi-threaded search for a keypair whose public key matches a pattern.
+#[derive(Debug, Clone)]
+pub struct VanitySearch {
+    matcher: Matcher,
+    threads: usize,
+    progress_interval: Duration,
+}
+
+impl VanitySearch {
+    /// Search for a legacy `EOS...` key, using one worker per available CPU.
+    pub fn new(pattern: &str, position: Position) -> crate::Result<Self> {
+        let matcher = Matcher::new(pattern, position, KeyFormat::Legacy, false)?;
+        Ok(Self::with_matcher(matcher))
+    }
+
+    pub fn with_matcher(matcher: Matcher) -> Self {
+        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        VanitySearch {
+            matcher,
+            threads,
+            progress_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Set the number of worker threads, at least one is used.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Set how often the progress callback is invoked.
+    pub fn progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    pub fn matcher(&self) -> &Matcher {
+        &self.matcher
+    }
+
+    /// Run the search until a key matches or `cancel` is triggered, calling
+    /// `on_progress` from the current thread at every progress interval.
+    pub fn run<F>(&self, cancel: &CancelToken, mut on_progress: F) -> Option<VanityMatch>
+        where F: FnMut(&Progress)
+    {
+        let start = Instant::now();
+        let attempts = Arc::new(AtomicU64::new(0));
+        let found = Arc::new(AtomicBool::new(false));
+        let (tx, rx) = mpsc::channel();
+
+        let workers: Vec<_> = (0..self.threads).map(|_| {
+            let matcher = self.matcher.clone();
+            let cancel = cancel.clone();
+            let attempts = attempts.clone();
+            let found = found.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let mut rng = rand::thread_rng();
+                let mut pending = 0u64;
+                while !cancel.is_cancelled() && !found.load(Ordering::Relaxed) {
+                    let keypair = Keypair::generate(&mut rng);
+                    pending += 1;
+                    if matcher.is_match(&keypair.pk) {
+                        attempts.fetch_add(pending, Ordering::Relaxed);
+                        found.store(true, Ordering::Relaxed);
+                        let _ = tx.send(keypair);
+                        return;
+                    }
+                    if pending == REPORT_BATCH {
+                        attempts.fetch_add(pending, Ordering::Relaxed);
+                        pending = 0;
+                    }
+                }
+                attempts.fetch_add(pending, Ordering::Relaxed);
+            })
+        }).collect();
+        // only the workers hold a sender now, so the channel closes once they all stop
+        drop(tx);
+
+        let keypair = loop {
+            match rx.recv_timeout(self.progress_interval) {
+                Ok(keypair) => break Some(keypair),
+                Err(mpsc::RecvTimeoutError::Timeout) => on_progress(&Progress {
+                    attempts: attempts.load(Ordering::Relaxed),
+                    elapsed: start.elapsed(),
+                }),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break None,
+            }
+        };
+
+        found.store(true, Ordering::Relaxed);
+        for worker in workers {
+            let _ = worker.join();
+        }
+
+        keypair.map(|keypair| VanityMatch {
+            keypair,
+            attempts: attempts.load(Ordering::Relaxed),
+            elapsed: start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+
+    fn public_key() -> PublicKey {
+        PublicKey::from_str("EOS8FdQ4gt16pFcSiXAYCcHnkHTS2nNLFWGZXW5sioAdvQuMxKhAm").unwrap()
+    }
+
+    #[test]
+    fn matcher_should_work() {
+        let pk = public_key();
+        let matches = |pattern, position, format, ignore_case| {
+            Matcher::new(pattern, position, format, ignore_case).unwrap().is_match(&pk)
+        };
+        assert!(matches("8FdQ", Position::Prefix, KeyFormat::Legacy, false));
+        assert!(!matches("8FdR", Position::Prefix, KeyFormat::Legacy, false));
+        assert!(matches("xKhAm", Position::Suffix, KeyFormat::Legacy, false));
+        assert!(matches("uMD9aL1", Position::Suffix, KeyFormat::K1, false));
+        assert!(!matches("xKhAm", Position::Suffix, KeyFormat::K1, false));
+        assert!(matches("gt16p", Position::Contains, KeyFormat::Legacy, false));
+        assert!(matches("GT16P", Position::Contains, KeyFormat::Legacy, true));
+        assert!(!matches("GT16P", Position::Contains, KeyFormat::Legacy, false));
+    }
+
+    #[test]
+    fn matcher_should_reject_invalid_patterns() {
+        assert!(Matcher::new("", Position::Prefix, KeyFormat::Legacy, false).is_err());
+        // '0', 'O', 'I' and 'l' are not part of the base58 alphabet
+        assert!(Matcher::new("c0de", Position::Contains, KeyFormat::Legacy, false).is_err());
+        assert!(Matcher::new("bOb", Position::Contains, KeyFormat::Legacy, false).is_err());
+        assert!(Matcher::new("bob", Position::Contains, KeyFormat::Legacy, true).is_ok());
+        // a compressed key never starts with 'E'
+        assert!(Matcher::new("Eos", Position::Prefix, KeyFormat::Legacy, false).is_err());
+        assert!(Matcher::new("Eos", Position::Suffix, KeyFormat::Legacy, false).is_ok());
+    }
+
+    #[test]
+    fn vanity_search_should_work() {
+        let search = VanitySearch::new("a", Position::Suffix).unwrap()
+            .threads(2)
+            .progress_interval(Duration::from_millis(10));
+        let result = search.run(&CancelToken::new(), |_| {});
+        assert!(result.is_some());
+
+        let result = result.unwrap();
+        assert!(result.attempts > 0);
+        assert_eq!(PublicKey::from(&result.keypair.sk), result.keypair.pk);
+        assert!(result.keypair.pk.to_eos_fmt().ends_with('a'));
+    }
+
+    #[test]
+    fn vanity_search_should_be_cancelled() {
+        let search = VanitySearch::new("zzzzzzzzzz", Position::Suffix).unwrap().threads(2);
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        assert!(search.run(&cancel, |_| {}).is_none());
+    }
+}
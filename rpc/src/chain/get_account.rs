@@ -1,8 +1,12 @@
+use alloc::collections::BTreeMap;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::str::FromStr;
 use crate::Client;
-use chain::names::{AccountName, PermissionName};
-use chain::permission_level::PermissionLevel;
+use chain::{AccountPermissions, Authority, AuthorityError, PermissionLevel, PermissionLink, PublicKey};
+use chain::names::{AccountName, ActionName, PermissionName};
+pub use chain::{PermissionLevelWeight, WaitWeight};
 use rpc_codegen::Fetch;
 use serde::{Deserialize, Serialize};
 
@@ -51,6 +55,16 @@ pub struct Permission {
     pub perm_name: PermissionName,
     pub parent: PermissionName,
     pub required_auth: RequiredAuth,
+    /// Only returned by nodes that report `linkauth` links
+    #[serde(default)]
+    pub linked_actions: Vec<LinkedAction>,
+}
+
+/// A `linkauth` link to the permission, no action means every action of the contract.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LinkedAction {
+    pub account: AccountName,
+    pub action: Option<ActionName>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -61,22 +75,72 @@ pub struct RequiredAuth {
     pub waits: Vec<WaitWeight>,
 }
 
+/// Keys are kept as strings, nodeos may return any key type here.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct PermissionLevelWeight {
-    pub permission: PermissionLevel,
-    pub weight: u16,
+pub struct KeyWeight {
+    pub key: String,
+    pub weight: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct WaitWeight {
-    pub wait_sec: u32,
-    pub weight: u16,
+impl TryFrom<&KeyWeight> for chain::KeyWeight {
+    type Error = chain::Error;
+
+    fn try_from(kw: &KeyWeight) -> Result<Self, Self::Error> {
+        let key = PublicKey::from_str(&kw.key)?;
+        let weight = u16::try_from(kw.weight).map_err(|_| AuthorityError::WeightOverflow(kw.weight))?;
+        Ok(Self { key, weight })
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct KeyWeight {
-    pub key: String,
-    pub weight: u32,
+impl TryFrom<&RequiredAuth> for Authority {
+    type Error = chain::Error;
+
+    fn try_from(auth: &RequiredAuth) -> Result<Self, Self::Error> {
+        let keys = auth.keys.iter()
+            .map(chain::KeyWeight::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            threshold: auth.threshold,
+            keys,
+            accounts: auth.accounts.clone(),
+            waits: auth.waits.clone(),
+        })
+    }
+}
+
+impl Permission {
+    /// Convert `required_auth` into a binary serializable authority.
+    pub fn authority(&self) -> chain::Result<Authority> {
+        Authority::try_from(&self.required_auth)
+    }
+}
+
+impl GetAccount {
+    /// The permission hierarchy and links of this account, for resolving `linkauth`.
+    pub fn account_permissions(&self) -> AccountPermissions {
+        let mut account = AccountPermissions::new(self.account_name);
+        for permission in self.permissions.iter() {
+            account.add_permission(permission.perm_name, permission.parent);
+            for linked in permission.linked_actions.iter() {
+                account.add_link(PermissionLink {
+                    code: linked.account,
+                    message_type: linked.action.unwrap_or_default(),
+                    required_permission: permission.perm_name,
+                });
+            }
+        }
+        account
+    }
+
+    /// Add the permissions of this account to a store for `AuthorityChecker`.
+    pub fn insert_permissions(&self, store: &mut BTreeMap<PermissionLevel, Authority>) -> chain::Result<()> {
+        for permission in self.permissions.iter() {
+            let level = PermissionLevel::new(self.account_name, permission.perm_name);
+            store.insert(level, permission.authority()?);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -114,6 +178,94 @@ pub struct VoterInfo {
     pub is_proxy: u8,
 }
 
+#[cfg(test)]
+mod authority_test {
+    use super::*;
+
+    #[test]
+    fn required_auth_to_authority_should_work() {
+        let json = r#"{
+            "perm_name": "active",
+            "parent": "owner",
+            "required_auth": {
+                "threshold": 2,
+                "keys": [{"key": "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV", "weight": 1}],
+                "accounts": [{"permission": {"actor": "bob", "permission": "active"}, "weight": 1}],
+                "waits": [{"wait_sec": 3600, "weight": 1}]
+            }
+        }"#;
+        let permission: Permission = serde_json::from_str(json).unwrap();
+        let auth = permission.authority().unwrap();
+        assert_eq!(auth.threshold, 2);
+        assert_eq!(auth.keys[0].key, PublicKey::from_str("EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV").unwrap());
+        assert_eq!(auth.accounts[0].permission.actor, AccountName::from_str("bob").unwrap());
+        assert_eq!(auth.waits[0].wait_sec, 3600);
+        assert!(auth.is_valid());
+    }
+
+    #[test]
+    fn linked_actions_to_account_permissions_should_work() {
+        let json = r#"{
+            "account_name": "alice",
+            "head_block_num": 100,
+            "head_block_time": "2020-01-01T00:00:00.000",
+            "privileged": false,
+            "last_code_update": "1970-01-01T00:00:00.000",
+            "created": "2019-01-01T00:00:00.000",
+            "ram_quota": 8192,
+            "net_weight": 0,
+            "cpu_weight": 0,
+            "net_limit": {"used": 0, "available": 0, "max": 0},
+            "cpu_limit": {"used": 0, "available": 0, "max": 0},
+            "ram_usage": 2996,
+            "permissions": [
+                {"perm_name": "active", "parent": "owner", "required_auth": {"threshold": 1, "keys": [], "accounts": [], "waits": []}},
+                {
+                    "perm_name": "bot",
+                    "parent": "active",
+                    "required_auth": {"threshold": 1, "keys": [], "accounts": [], "waits": []},
+                    "linked_actions": [{"account": "eosio.token", "action": "transfer"}, {"account": "dex"}]
+                },
+                {"perm_name": "owner", "parent": "", "required_auth": {"threshold": 1, "keys": [], "accounts": [], "waits": []}}
+            ],
+            "total_resources": null,
+            "self_delegated_bandwidth": null,
+            "refund_request": null,
+            "voter_info": null
+        }"#;
+        let account: GetAccount = serde_json::from_str(json).unwrap();
+        assert_eq!(account.permissions[1].linked_actions.len(), 2);
+        assert!(account.permissions[1].linked_actions[1].action.is_none());
+
+        let permissions = account.account_permissions();
+        let alice = AccountName::from_str("alice").unwrap();
+        let dex = AccountName::from_str("dex").unwrap();
+        let token = AccountName::from_str("eosio.token").unwrap();
+
+        let level = permissions.resolve(dex, ActionName::from_str("trade").unwrap()).unwrap();
+        assert_eq!(level, PermissionLevel::from_str("alice", "bot").unwrap());
+        let level = permissions.resolve(token, ActionName::from_str("transfer").unwrap()).unwrap();
+        assert_eq!(level, PermissionLevel::from_str("alice", "bot").unwrap());
+        // no link, falls back to active
+        let level = permissions.resolve(token, ActionName::from_str("open").unwrap()).unwrap();
+        assert_eq!(level, PermissionLevel::active(alice));
+    }
+
+    #[test]
+    fn required_auth_to_authority_should_error() {
+        let auth = RequiredAuth {
+            threshold: 1,
+            keys: vec![KeyWeight { key: "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV".into(), weight: 70000 }],
+            accounts: vec![],
+            waits: vec![],
+        };
+        match Authority::try_from(&auth) {
+            Err(chain::Error::InvalidAuthority(e)) => assert_eq!(e, AuthorityError::WeightOverflow(70000)),
+            _ => panic!("weight should overflow"),
+        }
+    }
+}
+
 #[cfg(feature = "use-hyper")]
 #[cfg(test)]
 mod test {
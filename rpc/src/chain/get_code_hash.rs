@@ -0,0 +1,48 @@
+use crate::Client;
+use chain::Checksum256;
+use chain::names::AccountName;
+use rpc_codegen::Fetch;
+use serde::{Deserialize, Serialize};
+
+#[derive(Fetch, Debug, Clone, Serialize)]
+#[api(path="v1/chain/get_code_hash", http_method="POST", returns="GetCodeHash")]
+pub struct GetCodeHashParams {
+    account_name: AccountName,
+}
+
+pub const fn get_code_hash(account_name: AccountName) -> GetCodeHashParams {
+    GetCodeHashParams { account_name }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetCodeHash {
+    pub account_name: AccountName,
+    /// All zeros when the account has no code
+    pub code_hash: Checksum256,
+}
+
+impl GetCodeHash {
+    /// The hash of the deployed code, `None` when the account has no code.
+    pub fn deployed_code_hash(&self) -> Option<Checksum256> {
+        Some(self.code_hash).filter(|hash| *hash != Checksum256::default())
+    }
+}
+
+#[cfg(feature = "use-hyper")]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::HyperClient;
+    use std::str::FromStr;
+
+    #[test]
+    fn get_code_hash_should_work() {
+        let node: &'static str = "https://eos.greymass.com/";
+        let hyper_client = HyperClient::new(node);
+
+        let account_name: AccountName = AccountName::from_str("eosio.token").unwrap();
+        let response = get_code_hash(account_name).fetch(&hyper_client);
+        assert!(response.is_ok());
+        assert!(response.unwrap().deployed_code_hash().is_some());
+    }
+}
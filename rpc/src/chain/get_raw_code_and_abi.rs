@@ -1,5 +1,7 @@
 use alloc::string::String;
+use alloc::vec::Vec;
 use crate::Client;
+use chain::{AbiDef, Checksum256};
 use chain::names::AccountName;
 use rpc_codegen::Fetch;
 use serde::{Deserialize, Serialize};
@@ -21,6 +23,65 @@ pub struct GetRawCodeAndAbi {
     pub abi: String,
 }
 
+impl GetRawCodeAndAbi {
+    pub fn wasm_bytes(&self) -> crate::Result<Vec<u8>> {
+        Ok(base64::decode(&self.wasm)?)
+    }
+
+    pub fn abi_bytes(&self) -> crate::Result<Vec<u8>> {
+        Ok(base64::decode(&self.abi)?)
+    }
+
+    /// The unpacked ABI, `None` when the account has no ABI.
+    pub fn abi_def(&self) -> crate::Result<Option<AbiDef>> {
+        let abi = self.abi_bytes()?;
+        if abi.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(AbiDef::from_bytes(&abi)?))
+    }
+
+    /// The hash of the deployed code, `None` when the account has no code.
+    pub fn code_hash(&self) -> crate::Result<Option<Checksum256>> {
+        let wasm = self.wasm_bytes()?;
+        Ok(Some(Checksum256::hash_from_slice(&wasm)).filter(|_| !wasm.is_empty()))
+    }
+
+    /// The hash of the deployed ABI, `None` when the account has no ABI.
+    pub fn abi_hash(&self) -> crate::Result<Option<Checksum256>> {
+        let abi = self.abi_bytes()?;
+        Ok(Some(Checksum256::hash_from_slice(&abi)).filter(|_| !abi.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod hash_test {
+    use super::*;
+
+    #[test]
+    fn raw_code_and_abi_hashes_should_work() {
+        let raw = GetRawCodeAndAbi {
+            account_name: "alice".into(),
+            wasm: "AGFzbQEAAAA=".into(),
+            abi: "DmVvc2lvOjphYmkvMS4xAAECaGkAAQR1c2VyBG5hbWUBAAAAAAAAgGsCaGkAAAAAAAAA".into(),
+        };
+        assert_eq!(
+            raw.code_hash().unwrap().unwrap().to_string(),
+            "93a44bbb96c751218e4c00d479e4c14358122a389acca16205b1e4d0dc5f9476"
+        );
+        assert_eq!(
+            raw.abi_hash().unwrap().unwrap().to_string(),
+            "00fee7c16a8accc30c360357566f95fb705e6bb53c5fd1b1d940e2bca789e839"
+        );
+        assert_eq!(raw.abi_def().unwrap().unwrap().version, "eosio::abi/1.1");
+
+        let empty = GetRawCodeAndAbi { account_name: "bob".into(), wasm: "".into(), abi: "".into() };
+        assert!(empty.code_hash().unwrap().is_none());
+        assert!(empty.abi_hash().unwrap().is_none());
+        assert!(empty.abi_def().unwrap().is_none());
+    }
+}
+
 #[cfg(feature = "use-hyper")]
 #[cfg(test)]
 mod test {
@@ -3,6 +3,7 @@ pub mod abi_json_to_bin;
 pub mod get_abi;
 pub mod get_account;
 pub mod get_block;
+pub mod get_code_hash;
 // pub mod get_block_header_state;
 pub mod get_currency_balance;
 pub mod get_currency_stats;
@@ -15,6 +16,7 @@ pub mod push_transaction;
 pub use self::get_abi::{get_abi, GetAbi, GetAbiParams};
 pub use self::get_account::{get_account, GetAccount, GetAccountParams};
 pub use self::get_block::{get_block, GetBlock, GetBlockParams};
+pub use self::get_code_hash::{get_code_hash, GetCodeHash, GetCodeHashParams};
 pub use self::get_currency_balance::{get_currency_balance, GetCurrencyBalance};
 pub use self::get_currency_stats::{get_currency_stats, GetCurrencyStats};
 pub use self::get_info::{get_info, GetInfo};
@@ -6,6 +6,12 @@ pub type Result<T> = core::result::Result<T, self::Error>;
 
 #[derive(Debug)]
 pub enum Error {
+    Base64Error {
+        base64_err: base64::DecodeError,
+    },
+    ChainError {
+        chain_err: chain::Error,
+    },
     EosError{
         eos_err: ErrorResponse,
     },
@@ -45,6 +51,8 @@ pub enum Error {
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
+            Self::Base64Error{ref base64_err} => write!(f, "Failed to decode base64 due to: {}.", base64_err),
+            Self::ChainError{ref chain_err} => write!(f, "Invalid chain data due to: {:?}.", chain_err),
             Self::EosError{ref eos_err} => write!(f, "Bad eos http response due to: {:?}.", eos_err),
             Self::HttpRequestError{ref request_err} => write!(f, "Bad hyper request due to: {}.", request_err),
             Self::HttpResponseError{ref response_err} => write!(f, "Bad hyper response due to: {}.", response_err),
@@ -63,6 +71,8 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
     fn description(&self) -> &str {
         match *self {
+            Self::Base64Error{..} => "Failed to decode base64.",
+            Self::ChainError{..} => "Invalid chain data.",
             Self::EosError{..} => "Bad eos http response.",
             Self::HttpRequestError{..} => "Bad hyper request.",
             Self::HttpResponseError{..} => "Bad hyper response.",
@@ -84,6 +94,18 @@ impl From<hyper::Error> for Error {
     }
 }
 
+impl From<base64::DecodeError> for Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Self::Base64Error { base64_err: err }
+    }
+}
+
+impl From<chain::Error> for Error {
+    fn from(err: chain::Error) -> Self {
+        Self::ChainError { chain_err: err }
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
         Error::ParseJsonError { serde_err: err }